@@ -1,16 +1,21 @@
-use std::env;
+use std::time::Duration;
 
-use anyhow::Result;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use reqwest::Url;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
     utils::{deserialize_date, serialize_date},
-    ACCOUNT_ACCESS_LEVEL,
-    API_BASE_URL,
+    COMPETITORS_CACHE_TTL,
+    SEASONS_CACHE_TTL,
+    STATS_CACHE_TTL,
 };
 
+// Fixed TTLs of the endpoints
+const HOUR: Duration = Duration::from_secs(60 * 60);
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 /// API endpoints for the soccer API
 pub struct ApiEndpoints {
     base_url: Url,
@@ -18,47 +23,208 @@ pub struct ApiEndpoints {
 
 impl ApiEndpoints {
     /// Create a new instance of the API endpoints
-    pub fn new() -> Result<Self, url::ParseError> {
-        let base_url = env::var("API_BASE_URL").unwrap_or_else(|_| API_BASE_URL.into());
-        let access_level =
-            env::var("ACCOUNT_ACCESS_LEVEL").unwrap_or_else(|_| ACCOUNT_ACCESS_LEVEL.into());
-
+    pub fn new(base_url: &str, access_level: &str) -> Result<Self, url::ParseError> {
         Ok(ApiEndpoints {
             base_url: Url::parse(&format!("{}/soccer/{}/v4/en/", base_url, access_level))?,
         })
     }
 
-    /// Get the URL for the competition seasons endpoint
-    pub fn competition_seasons(&self, competition_id: &str) -> Result<Url, url::ParseError> {
-        self.base_url
-            .join(&format!("competitions/{}/seasons.json", competition_id))
+    /// Get the URL of an endpoint
+    pub fn url(&self, endpoint: &impl Endpoint) -> Result<Url, url::ParseError> {
+        self.base_url.join(&endpoint.path())
+    }
+}
+
+/// Resource of the Soccer v4 API, tied to the type it responds with so it
+/// can't be fetched as the wrong one
+pub trait Endpoint {
+    /// Type the endpoint responds with
+    type Response: Serialize + DeserializeOwned;
+
+    /// How long a cached response of the endpoint stays fresh. Schedules and
+    /// standings change after every match, while seasons and profiles barely
+    /// change
+    const TTL: CacheTtl;
+
+    /// Path of the endpoint, relative to the API's base url
+    fn path(&self) -> String;
+
+    /// How long a cached response of the endpoint stays fresh by default
+    fn default_ttl(&self) -> Duration {
+        match Self::TTL {
+            CacheTtl::Seasons => SEASONS_CACHE_TTL,
+            CacheTtl::Competitors => COMPETITORS_CACHE_TTL,
+            CacheTtl::Stats => STATS_CACHE_TTL,
+            CacheTtl::Fixed(ttl) => ttl,
+        }
+    }
+
+    /// Parts of the path the response of the endpoint is cached under
+    fn cache_key(&self) -> Vec<String> {
+        self.path().split('/').map(str::to_string).collect()
+    }
+}
+
+/// How long cached responses of an [`Endpoint`] stay fresh
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheTtl {
+    /// The configurable TTL of competition seasons
+    Seasons,
+    /// The configurable TTL of season competitors
+    Competitors,
+    /// The configurable TTL of player statistics
+    Stats,
+    /// A TTL that can't be configured
+    Fixed(Duration),
+}
+
+/// Every competition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Competitions;
+
+/// Seasons of a competition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompetitionSeasons<'a> {
+    pub competition_id: &'a str,
+}
+
+/// Information about a season
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonInfo<'a> {
+    pub season_id: &'a str,
+}
+
+/// Competitors taking part in a season
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonCompetitors<'a> {
+    pub season_id: &'a str,
+}
+
+/// Matches of a season
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonSchedules<'a> {
+    pub season_id: &'a str,
+}
+
+/// Standings of a season
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeasonStandings<'a> {
+    pub season_id: &'a str,
+}
+
+/// Profile of a competitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompetitorProfile<'a> {
+    pub competitor_id: &'a str,
+}
+
+/// Statistics of a competitor's players in a season
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompetitorStatistics<'a> {
+    pub season_id: &'a str,
+    pub competitor_id: &'a str,
+}
+
+impl Endpoint for Competitions {
+    type Response = ApiCompetitions;
+
+    const TTL: CacheTtl = CacheTtl::Fixed(WEEK);
+
+    fn path(&self) -> String {
+        "competitions.json".to_string()
+    }
+}
+
+impl Endpoint for CompetitionSeasons<'_> {
+    type Response = ApiCompetitionSeasons;
+
+    const TTL: CacheTtl = CacheTtl::Seasons;
+
+    fn path(&self) -> String {
+        format!("competitions/{}/seasons.json", self.competition_id)
+    }
+}
+
+impl Endpoint for SeasonInfo<'_> {
+    type Response = ApiSeasonInfo;
+
+    const TTL: CacheTtl = CacheTtl::Fixed(WEEK);
+
+    fn path(&self) -> String {
+        format!("seasons/{}/info.json", self.season_id)
+    }
+}
+
+impl Endpoint for SeasonCompetitors<'_> {
+    type Response = ApiSeasonCompetitors;
+
+    const TTL: CacheTtl = CacheTtl::Competitors;
+
+    fn path(&self) -> String {
+        format!("seasons/{}/competitors.json", self.season_id)
+    }
+}
+
+impl Endpoint for SeasonSchedules<'_> {
+    type Response = ApiSeasonSchedules;
+
+    const TTL: CacheTtl = CacheTtl::Fixed(HOUR);
+
+    fn path(&self) -> String {
+        format!("seasons/{}/schedules.json", self.season_id)
     }
+}
+
+impl Endpoint for SeasonStandings<'_> {
+    type Response = ApiSeasonStandings;
+
+    const TTL: CacheTtl = CacheTtl::Fixed(HOUR);
+
+    fn path(&self) -> String {
+        format!("seasons/{}/standings.json", self.season_id)
+    }
+}
+
+impl Endpoint for CompetitorProfile<'_> {
+    type Response = ApiCompetitorProfile;
+
+    const TTL: CacheTtl = CacheTtl::Fixed(DAY);
 
-    /// Get the URL for the season competitions endpoint
-    pub fn season_competitors(&self, season_id: &str) -> Result<Url, url::ParseError> {
-        self.base_url
-            .join(&format!("seasons/{}/competitors.json", season_id))
+    fn path(&self) -> String {
+        format!("competitors/{}/profile.json", self.competitor_id)
     }
+}
+
+impl Endpoint for CompetitorStatistics<'_> {
+    type Response = ApiSeasonCompetitorStatistics;
 
-    /// Get the URL for the competitor statistics endpoint
-    pub fn competitor_statistics(
-        &self,
-        season_id: &str,
-        competition_id: &str,
-    ) -> Result<Url, url::ParseError> {
-        self.base_url.join(&format!(
+    const TTL: CacheTtl = CacheTtl::Stats;
+
+    fn path(&self) -> String {
+        format!(
             "seasons/{}/competitors/{}/statistics.json",
-            season_id, competition_id
-        ))
+            self.season_id, self.competitor_id
+        )
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiCompetitions {
+    pub competitions: Vec<ApiCompetition>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiCompetition {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiCompetitionSeasons {
     pub seasons: Vec<ApiCompetitionSeason>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ApiCompetitionSeason {
     pub id: String,
     pub name: String,
@@ -76,13 +242,115 @@ pub struct ApiCompetitionSeason {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct ApiSeasonSchedules {
-    pub schedules: Vec<ApiSportEvent>,
+pub struct ApiSeasonInfo {
+    pub season: ApiCompetitionSeason,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+pub struct ApiSeasonSchedules {
+    pub schedules: Vec<ApiSchedule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiSchedule {
+    pub sport_event: ApiSportEvent,
+    pub sport_event_status: ApiSportEventStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ApiSportEvent {
     pub id: String,
+    pub start_time: DateTime<Utc>,
+    #[serde(default)]
+    pub competitors: Vec<ApiSportEventCompetitor>,
+    pub venue: Option<ApiVenue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiSportEventCompetitor {
+    pub id: String,
+    pub name: String,
+    pub abbreviation: Option<String>,
+    /// Either `home` or `away`
+    pub qualifier: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiVenue {
+    pub id: String,
+    pub name: String,
+    pub city_name: Option<String>,
+    pub country_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiSportEventStatus {
+    /// `not_started`, `live`, `closed`, `ended`, `postponed`, `cancelled`...
+    pub status: String,
+    pub match_status: Option<String>,
+    pub home_score: Option<u16>,
+    pub away_score: Option<u16>,
+    pub winner_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiSeasonStandings {
+    pub standings: Vec<ApiStanding>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiStanding {
+    /// `total`, `home` or `away`
+    #[serde(rename = "type")]
+    pub standing_type: String,
+    pub groups: Vec<ApiStandingGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiStandingGroup {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub standings: Vec<ApiStandingRow>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiStandingRow {
+    pub rank: u16,
+    pub competitor: ApiCompetitor,
+    pub played: u16,
+    pub win: u16,
+    pub draw: u16,
+    pub loss: u16,
+    pub goals_for: u16,
+    pub goals_against: u16,
+    pub goals_diff: i16,
+    pub points: u16,
+    /// Results of the last matches, e.g. `WWDLW`
+    pub form: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiCompetitorProfile {
+    pub competitor: ApiCompetitor,
+    #[serde(default)]
+    pub players: Vec<ApiPlayer>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiCompetitor {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiPlayer {
+    pub id: String,
+    pub name: String,
+    /// `goalkeeper`, `defender`, `midfielder` or `forward`
+    #[serde(rename = "type")]
+    pub position: Option<String>,
+    pub nationality: Option<String>,
+    pub date_of_birth: Option<NaiveDate>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -104,6 +372,8 @@ pub struct ApiSeasonCompetitorStatistics {
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ApiSeasonCompetitorStatistic {
     pub id: String,
+    #[serde(default)]
+    pub name: String,
     pub statistics: ApiGameStatistic,
     pub players: Vec<ApiGamePlayers>,
 }
@@ -120,8 +390,66 @@ pub struct ApiGamePlayers {
     pub statistics: ApiPlayerGameStatistic,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// Statistics of a player, fields the API leaves out are zero or not
+/// available for the account's access level
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct ApiPlayerGameStatistic {
+    #[serde(default)]
     pub goals_scored: u16,
+    #[serde(default)]
     pub assists: u16,
+    pub matches_played: Option<u16>,
+    pub minutes_played: Option<u16>,
+    pub shots_on_target: Option<u16>,
+    pub shots_off_target: Option<u16>,
+    pub shots_blocked: Option<u16>,
+    pub yellow_cards: Option<u16>,
+    pub yellow_red_cards: Option<u16>,
+    pub red_cards: Option<u16>,
+    pub substituted_in: Option<u16>,
+    pub substituted_out: Option<u16>,
+    pub goals_by_penalty: Option<u16>,
+    pub penalties_missed: Option<u16>,
+    pub own_goals: Option<u16>,
+    pub offsides: Option<u16>,
+    pub corner_kicks: Option<u16>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test endpoint urls are built from the base url
+    #[test]
+    fn test_endpoint_url() {
+        let api_endpoints = ApiEndpoints::new("https://api.sportradar.com", "trial").unwrap();
+
+        let endpoint = CompetitorStatistics {
+            season_id: "sr:season:1",
+            competitor_id: "sr:competitor:2",
+        };
+
+        assert_eq!(
+            api_endpoints.url(&endpoint).unwrap().as_str(),
+            "https://api.sportradar.com/soccer/trial/v4/en/seasons/sr:season:1/competitors/sr:competitor:2/statistics.json"
+        );
+        assert_eq!(
+            endpoint.cache_key(),
+            [
+                "seasons",
+                "sr:season:1",
+                "competitors",
+                "sr:competitor:2",
+                "statistics.json"
+            ]
+        );
+        assert_eq!(endpoint.default_ttl(), Duration::from_secs(6 * 60 * 60));
+
+        let endpoint = SeasonSchedules {
+            season_id: "sr:season:1",
+        };
+
+        assert_eq!(endpoint.path(), "seasons/sr:season:1/schedules.json");
+        assert_eq!(endpoint.default_ttl(), Duration::from_secs(60 * 60));
+    }
 }
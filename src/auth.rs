@@ -0,0 +1,116 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use clap::ValueEnum;
+use reqwest::Url;
+
+/// Name of the header Sportradar reads the API key from
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Name of the query parameter Sportradar reads the API key from
+pub const API_KEY_QUERY_PARAM: &str = "api_key";
+
+/// Text shown instead of secrets
+const REDACTED: &str = "[REDACTED]";
+
+/// Sportradar account's API key. It is redacted when printed with `Debug` or
+/// `Display`, so it can't leak into logs by accident
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    /// Create a new [`ApiKey`]
+    pub fn new(key: &str) -> Self {
+        Self(key.to_string())
+    }
+
+    /// Get the actual value of the key, to authorize a request
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "ApiKey({})", REDACTED)
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", REDACTED)
+    }
+}
+
+impl FromStr for ApiKey {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+/// How requests are authorized with the [`ApiKey`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum AuthMethod {
+    /// Send the key in the `x-api-key` header
+    #[default]
+    Header,
+    /// Send the key in the `api_key` query parameter
+    Query,
+}
+
+/// Redact the API key from a URL before it is printed or stored
+pub fn redact_url(url: &Url) -> String {
+    if !url.query_pairs().any(|(key, _)| key == API_KEY_QUERY_PARAM) {
+        return url.to_string();
+    }
+
+    let mut url = url.clone();
+    let query: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(key, value)| {
+            let value = if key == API_KEY_QUERY_PARAM {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+
+            (key.into_owned(), value)
+        })
+        .collect();
+
+    url.query_pairs_mut().clear().extend_pairs(query);
+
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test the api key is never printed
+    #[test]
+    fn test_api_key_redacted() {
+        let api_key = ApiKey::new("secret");
+
+        assert_eq!(api_key.expose(), "secret");
+        assert!(!format!("{}", api_key).contains("secret"));
+        assert!(!format!("{:?}", api_key).contains("secret"));
+    }
+
+    // test the api key is redacted from urls
+    #[test]
+    fn test_redact_url() {
+        let url =
+            Url::parse("https://api.sportradar.com/seasons.json?api_key=secret&page=2").unwrap();
+
+        let redacted = redact_url(&url);
+
+        assert!(!redacted.contains("secret"));
+        assert!(redacted.contains("page=2"));
+
+        let url = Url::parse("https://api.sportradar.com/seasons.json").unwrap();
+
+        assert_eq!(redact_url(&url), "https://api.sportradar.com/seasons.json");
+    }
+}
@@ -0,0 +1,599 @@
+use std::{collections::BTreeMap, fmt, path::PathBuf, str::FromStr, time::Duration};
+
+use chrono::NaiveDate;
+use clap::{parser::ValueSource, ArgAction, ArgMatches, Args, Parser, Subcommand, ValueEnum};
+use log::LevelFilter;
+use serde_json::Value;
+use talent_scout::{
+    auth::{ApiKey, AuthMethod},
+    config::{Config, ConfigError, Settings},
+    output::OutputFormat,
+    player::{IngestPolicy, Metric, StatKey, TiePolicy, LEADERBOARD_LIMIT, MIN_MINUTES},
+    rate_limit::MIN_RATE_LIMIT,
+    season::{CompetitionSelector, SeasonSelector},
+    standings::StandingType,
+    utils::CacheMode,
+    ACCOUNT_ACCESS_LEVEL,
+    API_BASE_URL,
+    CACHE_LOCATION,
+    COMPETITION_ID,
+    COMPETITORS_CACHE_TTL,
+    MAX_RETRIES,
+    SEASONS_CACHE_TTL,
+    STATS_CACHE_TTL,
+};
+
+/// Find the top scorers and assist providers of a competition using the
+/// Sportradar Soccer API
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub api: ApiArgs,
+
+    #[command(flatten)]
+    pub cache: CacheArgs,
+
+    #[command(flatten)]
+    pub leaderboard: LeaderboardArgs,
+
+    /// Format the output is printed in
+    #[arg(
+        long,
+        env = "OUTPUT_FORMAT",
+        global = true,
+        value_enum,
+        default_value_t
+    )]
+    pub format: OutputFormat,
+
+    /// Print more progress messages on stderr, repeat for more detail (-vv)
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+
+    /// Print fewer messages on stderr, only errors with -q and nothing with
+    /// -qq
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub quiet: u8,
+
+    /// Config file to read instead of the user-level and project-local ones
+    #[arg(long, env = "TALENT_SCOUT_CONFIG", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Profile of the config files to use, e.g. `prod`
+    #[arg(long, env = "TALENT_SCOUT_PROFILE", global = true)]
+    pub profile: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Where the value of each setting came from
+    #[arg(skip)]
+    pub sources: BTreeMap<&'static str, SettingSource>,
+}
+
+/// Settings that can be set in the config files, named after their flag
+pub const SETTINGS: &[&str] = &[
+    "api_key",
+    "auth",
+    "api_base_url",
+    "access_level",
+    "rate_limit",
+    "max_retries",
+    "competition_id",
+    "season",
+    "cache_location",
+    "profiles",
+    "players",
+    "seasons_ttl",
+    "competitors_ttl",
+    "stats_ttl",
+    "limit",
+    "ties",
+    "min_minutes",
+    "per_competition",
+    "format",
+];
+
+/// Where the value of a setting came from, from the lowest to the highest
+/// precedence
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SettingSource {
+    #[default]
+    Default,
+    File(PathBuf),
+    Env,
+    CommandLine,
+}
+
+impl fmt::Display for SettingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingSource::Default => write!(f, "default"),
+            SettingSource::File(path) => write!(f, "{}", path.display()),
+            SettingSource::Env => write!(f, "env"),
+            SettingSource::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+impl Cli {
+    /// Level of the messages printed on stderr. Warnings by default, each
+    /// `-v` adds a level and each `-q` removes one
+    pub fn log_level(&self) -> LevelFilter {
+        match self.verbose as i8 - self.quiet as i8 {
+            ..=-2 => LevelFilter::Off,
+            -1 => LevelFilter::Error,
+            0 => LevelFilter::Warn,
+            1 => LevelFilter::Info,
+            2 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    /// Fill in the settings that weren't passed as flags or environment
+    /// variables from the config files, recording where each setting came
+    /// from
+    pub fn load_config(&mut self, matches: &ArgMatches) -> Result<(), ConfigError> {
+        let paths = match &self.config {
+            Some(path) if !path.exists() => {
+                return Err(ConfigError::Io {
+                    path: path.clone(),
+                    source: std::io::ErrorKind::NotFound.into(),
+                })
+            },
+            Some(path) => vec![path.clone()],
+            None => Config::default_paths(),
+        };
+
+        let config = Config::load(&paths)?;
+        let layers = config.layers(self.profile.as_deref())?;
+
+        for key in SETTINGS {
+            let source = match matches.value_source(key) {
+                Some(ValueSource::CommandLine) => SettingSource::CommandLine,
+                Some(ValueSource::EnvVariable) => SettingSource::Env,
+                _ => {
+                    let mut source = SettingSource::Default;
+
+                    // later layers override the earlier ones
+                    for (settings, path) in layers.iter() {
+                        let set =
+                            self.set(key, settings)
+                                .map_err(|message| ConfigError::Setting {
+                                    path: path.to_path_buf(),
+                                    key: key.to_string(),
+                                    message,
+                                })?;
+
+                        if set {
+                            source = SettingSource::File(path.to_path_buf());
+                        }
+                    }
+
+                    source
+                },
+            };
+
+            self.sources.insert(key, source);
+        }
+
+        Ok(())
+    }
+
+    /// Set a setting from its value in a config file, returns whether the
+    /// file sets it
+    fn set(&mut self, key: &str, settings: &Settings) -> Result<bool, String> {
+        match key {
+            "api_key" => set(&settings.api_key, |value| {
+                self.api.api_key = Some(ApiKey::new(value));
+                Ok(())
+            }),
+            "auth" => set(&settings.auth, |value| {
+                self.api.auth = AuthMethod::from_str(value, true)?;
+                Ok(())
+            }),
+            "api_base_url" => set(&settings.api_base_url, |value| {
+                self.api.api_base_url = value.clone();
+                Ok(())
+            }),
+            "access_level" => set(&settings.access_level, |value| {
+                self.api.access_level = value.clone();
+                Ok(())
+            }),
+            "rate_limit" => set(&settings.rate_limit, |value| {
+                self.api.rate_limit = Some(check_rate_limit(*value)?);
+                Ok(())
+            }),
+            "max_retries" => set(&settings.max_retries, |value| {
+                self.api.max_retries = *value;
+                Ok(())
+            }),
+            "competition_id" => set(&settings.competition_id, |value| {
+                self.api.competitions = value
+                    .to_vec()
+                    .into_iter()
+                    .map(parse)
+                    .collect::<Result<_, _>>()?;
+                Ok(())
+            }),
+            "season" => set(&settings.season, |value| {
+                self.api.season = parse(value)?;
+                Ok(())
+            }),
+            "cache_location" => set(&settings.cache_location, |value| {
+                self.api.cache_location = value.clone();
+                Ok(())
+            }),
+            "profiles" => set(&settings.profiles, |value| {
+                self.api.profiles = *value;
+                Ok(())
+            }),
+            "players" => set(&settings.players, |value| {
+                self.api.players = IngestPolicy::from_str(value, true)?;
+                Ok(())
+            }),
+            "seasons_ttl" => set(&settings.seasons_ttl, |value| {
+                self.cache.seasons_ttl = parse_duration(value)?.into();
+                Ok(())
+            }),
+            "competitors_ttl" => set(&settings.competitors_ttl, |value| {
+                self.cache.competitors_ttl = parse_duration(value)?.into();
+                Ok(())
+            }),
+            "stats_ttl" => set(&settings.stats_ttl, |value| {
+                self.cache.stats_ttl = parse_duration(value)?.into();
+                Ok(())
+            }),
+            "limit" => set(&settings.limit, |value| {
+                self.leaderboard.limit = *value;
+                Ok(())
+            }),
+            "ties" => set(&settings.ties, |value| {
+                self.leaderboard.ties = TiePolicy::from_str(value, true)?;
+                Ok(())
+            }),
+            "min_minutes" => set(&settings.min_minutes, |value| {
+                self.leaderboard.min_minutes = *value;
+                Ok(())
+            }),
+            "per_competition" => set(&settings.per_competition, |value| {
+                self.leaderboard.per_competition = *value;
+                Ok(())
+            }),
+            "format" => set(&settings.format, |value| {
+                self.format = OutputFormat::from_str(value, true)?;
+                Ok(())
+            }),
+            _ => Err("unknown setting".to_string()),
+        }
+    }
+
+    /// Get the resolved value of a setting, with the API key redacted
+    pub fn get(&self, key: &str) -> Value {
+        match key {
+            "api_key" => self.api.api_key.as_ref().map(|key| key.to_string()).into(),
+            "auth" => value_name(self.api.auth).into(),
+            "api_base_url" => self.api.api_base_url.clone().into(),
+            "access_level" => self.api.access_level.clone().into(),
+            "rate_limit" => self.api.rate_limit.into(),
+            "max_retries" => self.api.max_retries.into(),
+            "competition_id" => self
+                .api
+                .competitions
+                .iter()
+                .map(|competition| competition.to_string())
+                .collect::<Vec<_>>()
+                .into(),
+            "season" => self.api.season.to_string().into(),
+            "cache_location" => self.api.cache_location.clone().into(),
+            "profiles" => self.api.profiles.into(),
+            "players" => value_name(self.api.players).into(),
+            "seasons_ttl" => self.cache.seasons_ttl.to_string().into(),
+            "competitors_ttl" => self.cache.competitors_ttl.to_string().into(),
+            "stats_ttl" => self.cache.stats_ttl.to_string().into(),
+            "limit" => self.leaderboard.limit.into(),
+            "ties" => value_name(self.leaderboard.ties).into(),
+            "min_minutes" => self.leaderboard.min_minutes.into(),
+            "per_competition" => self.leaderboard.per_competition.into(),
+            "format" => value_name(self.format).into(),
+            _ => Value::Null,
+        }
+    }
+}
+
+/// Parse the value of a setting
+fn parse<T>(value: &str) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value.parse().map_err(|err: T::Err| err.to_string())
+}
+
+/// Apply the value of a setting when a config file sets it
+fn set<T>(value: &Option<T>, apply: impl FnOnce(&T) -> Result<(), String>) -> Result<bool, String> {
+    match value {
+        Some(value) => apply(value).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// Parse a rate limit, a number of requests per second
+fn parse_rate_limit(value: &str) -> Result<f64, String> {
+    check_rate_limit(parse(value)?)
+}
+
+/// Check a rate limit is a number of requests per second of at least
+/// [`MIN_RATE_LIMIT`]
+fn check_rate_limit(requests_per_second: f64) -> Result<f64, String> {
+    if !requests_per_second.is_finite() || requests_per_second < MIN_RATE_LIMIT {
+        return Err(format!(
+            "expected at least {} requests per second",
+            MIN_RATE_LIMIT
+        ));
+    }
+
+    Ok(requests_per_second)
+}
+
+/// Parse the value of a TTL setting, e.g. `6h`
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    humantime::parse_duration(value).map_err(|err| err.to_string())
+}
+
+/// Name of a value of a [`ValueEnum`] on the command line
+fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Arguments shared by all commands. Every flag falls back to the environment
+/// variable of the same name when it is not passed
+#[derive(Args, Debug)]
+pub struct ApiArgs {
+    /// Sportradar account's API key
+    #[arg(long, env = "API_KEY", global = true, hide_env_values = true)]
+    pub api_key: Option<ApiKey>,
+
+    /// How requests are authorized with the API key
+    #[arg(long, env = "API_AUTH", global = true, value_enum, default_value_t)]
+    pub auth: AuthMethod,
+
+    /// API base url for sportradar's API
+    #[arg(long, env = "API_BASE_URL", global = true, default_value = API_BASE_URL)]
+    pub api_base_url: String,
+
+    /// Sportradar account access level
+    #[arg(long, env = "ACCOUNT_ACCESS_LEVEL", global = true, default_value = ACCOUNT_ACCESS_LEVEL)]
+    pub access_level: String,
+
+    /// Maximum requests per second made to the API, defaults to the limit of
+    /// the access level
+    #[arg(long, env = "RATE_LIMIT", global = true, value_parser = parse_rate_limit)]
+    pub rate_limit: Option<f64>,
+
+    /// Number of times a request is retried after a rate limit, timeout or
+    /// server error
+    #[arg(long, env = "MAX_RETRIES", global = true, default_value_t = MAX_RETRIES)]
+    pub max_retries: u32,
+
+    /// Competitions to get stats for, separated by commas. Each competition
+    /// can select its own season, e.g. `sr:competition:17@23/24`
+    #[arg(
+        id = "competition_id",
+        long = "competition-id",
+        env = "COMPETITION_ID",
+        global = true,
+        value_delimiter = ',',
+        default_value = COMPETITION_ID
+    )]
+    pub competitions: Vec<CompetitionSelector>,
+
+    /// Season to get stats for: a season id, name, year, `current`, `latest`
+    /// or `last-<n>` for the stats of the last n seasons. Used by the
+    /// competitions not selecting their own season
+    #[arg(long, env = "SEASON", global = true, default_value = "current")]
+    pub season: SeasonSelector,
+
+    /// Location to store cache
+    #[arg(long, env = "CACHE_LOCATION", global = true, default_value = CACHE_LOCATION)]
+    pub cache_location: String,
+
+    /// Also fetch the competitors' profiles, for the position, nationality and
+    /// date of birth of players
+    #[arg(long, env = "PLAYER_PROFILES", global = true)]
+    pub profiles: bool,
+
+    /// Which players of the competitor statistics are ranked
+    #[arg(
+        long,
+        env = "PLAYER_INGEST",
+        global = true,
+        value_enum,
+        default_value_t
+    )]
+    pub players: IngestPolicy,
+}
+
+impl ApiArgs {
+    /// The first competition, used by the commands listing the seasons,
+    /// competitors, fixtures or standings of a single competition
+    pub fn competition(&self) -> &CompetitionSelector {
+        &self.competitions[0]
+    }
+}
+
+/// Arguments controlling how long cached responses are used for
+#[derive(Args, Debug)]
+pub struct CacheArgs {
+    /// Ignore the cache and request everything again, updating the cache
+    #[arg(long, global = true, conflicts_with = "offline")]
+    pub refresh: bool,
+
+    /// Only use the cache regardless of its age, never make requests
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// How long cached competition seasons stay fresh, e.g. `7d`
+    #[arg(long, env = "SEASONS_CACHE_TTL", global = true, default_value_t = SEASONS_CACHE_TTL.into())]
+    pub seasons_ttl: humantime::Duration,
+
+    /// How long cached season competitors stay fresh, e.g. `1d`
+    #[arg(long, env = "COMPETITORS_CACHE_TTL", global = true, default_value_t = COMPETITORS_CACHE_TTL.into())]
+    pub competitors_ttl: humantime::Duration,
+
+    /// How long cached competitor statistics stay fresh, e.g. `6h`
+    #[arg(long, env = "STATS_CACHE_TTL", global = true, default_value_t = STATS_CACHE_TTL.into())]
+    pub stats_ttl: humantime::Duration,
+}
+
+impl CacheArgs {
+    /// Get the [`CacheMode`] selected by the flags
+    pub fn mode(&self) -> CacheMode {
+        if self.refresh {
+            CacheMode::Refresh
+        } else if self.offline {
+            CacheMode::Offline
+        } else {
+            CacheMode::Default
+        }
+    }
+}
+
+/// Arguments controlling the size of the leaderboards
+#[derive(Args, Debug)]
+pub struct LeaderboardArgs {
+    /// Number of players to show in each leaderboard
+    #[arg(long, env = "LEADERBOARD_LIMIT", global = true, default_value_t = LEADERBOARD_LIMIT)]
+    pub limit: usize,
+
+    /// How to handle players tied around the limit
+    #[arg(
+        long,
+        env = "LEADERBOARD_TIES",
+        global = true,
+        value_enum,
+        default_value_t
+    )]
+    pub ties: TiePolicy,
+
+    /// Minutes a player must have played to be ranked by an efficiency metric
+    #[arg(long, env = "LEADERBOARD_MIN_MINUTES", global = true, default_value_t = MIN_MINUTES)]
+    pub min_minutes: u16,
+
+    /// Print separate leaderboards for each competition, instead of ranking
+    /// the players of every competition together
+    #[arg(long, env = "LEADERBOARD_PER_COMPETITION", global = true)]
+    pub per_competition: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(flatten)]
+    Api(ApiCommand),
+
+    /// Inspect or clear the local cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Inspect the settings resolved from the config files, environment and
+    /// flags
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+/// Commands that fetch data from the API
+#[derive(Subcommand, Debug, Default)]
+pub enum ApiCommand {
+    /// Print both the top scorers and the top assists (default)
+    #[default]
+    Leaders,
+
+    /// Print the players who scored the most
+    TopScorers,
+
+    /// Print the players who assisted the most
+    TopAssists,
+
+    /// Print a leaderboard for each of the given stats
+    Top {
+        /// Stats to rank players by
+        #[arg(value_enum, required = true)]
+        stats: Vec<StatKey>,
+    },
+
+    /// Print a leaderboard for each of the given efficiency metrics
+    Efficiency {
+        /// Metrics to rank players by
+        #[arg(value_enum, required = true)]
+        metrics: Vec<Metric>,
+    },
+
+    /// Print the stats of players season by season, and their totals over
+    /// the seasons, e.g. with `--season last-3`
+    History {
+        /// Player id, e.g. sr:player:1, or part of the player's name
+        player: String,
+    },
+
+    /// Fetch all data required for the leaderboards and store it in the cache
+    Fetch,
+
+    /// List the seasons of the competition
+    Seasons,
+
+    /// List the competitors taking part in the season
+    Competitors,
+
+    /// List the played and upcoming matches of the season
+    Fixtures {
+        /// Only list matches of a team, by its id or part of its name
+        #[arg(long)]
+        team: Option<String>,
+
+        /// Only list matches on or after this date, e.g. 2024-01-01
+        #[arg(long)]
+        from: Option<NaiveDate>,
+
+        /// Only list matches on or before this date, e.g. 2024-05-19
+        #[arg(long)]
+        to: Option<NaiveDate>,
+    },
+
+    /// Print the standings of the season
+    Standings {
+        /// Which matches the standings are computed from
+        #[arg(long = "type", value_enum, default_value_t)]
+        standing_type: StandingType,
+    },
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Command::Api(ApiCommand::default())
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheCommand {
+    /// List the files stored in the cache
+    List,
+
+    /// Remove the cache entries, refusing to touch a directory holding other
+    /// files
+    Clear,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the resolved value of every setting and where it came from, with
+    /// the API key redacted
+    Show,
+}
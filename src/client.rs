@@ -0,0 +1,450 @@
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use log::{info, warn};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT},
+    Client,
+    RequestBuilder,
+    Url,
+};
+use serde::Deserialize;
+use tokio::time::sleep;
+
+use crate::{
+    api::{
+        ApiCompetitionSeasons,
+        ApiCompetitorProfile,
+        ApiEndpoints,
+        ApiSeasonCompetitor,
+        ApiSeasonCompetitorStatistics,
+        ApiSeasonCompetitors,
+        ApiSeasonSchedules,
+        ApiSeasonStandings,
+        CacheTtl,
+        CompetitionSeasons,
+        CompetitorProfile,
+        CompetitorStatistics,
+        Endpoint,
+        SeasonCompetitors,
+        SeasonSchedules,
+        SeasonStandings,
+    },
+    auth::{redact_url, ApiKey, AuthMethod, API_KEY_HEADER, API_KEY_QUERY_PARAM},
+    error::{Error, Result},
+    player::{IngestPolicy, PlayerDB},
+    rate_limit::RateLimiter,
+    retry::RetryPolicy,
+    utils::{cached, read_from_cache, write_to_cache, Cache, CacheEntry, CacheMode, Lookup},
+    ACCOUNT_ACCESS_LEVEL,
+    API_BASE_URL,
+    CACHE_LOCATION,
+    COMPETITORS_CACHE_TTL,
+    MAX_RETRIES,
+    SEASONS_CACHE_TTL,
+    STATS_CACHE_TTL,
+};
+
+/// User agent sent with every request
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Configuration of a [`SportradarClient`]
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Sportradar account's API key
+    pub api_key: ApiKey,
+    /// How requests are authorized with the API key
+    pub auth_method: AuthMethod,
+    /// API base url for sportradar's API
+    pub api_base_url: String,
+    /// Sportradar account access level
+    pub access_level: String,
+    /// Location to store cache
+    pub cache_location: String,
+    /// How the cache is used
+    pub cache_mode: CacheMode,
+    /// How long cached competition seasons stay fresh
+    pub seasons_ttl: Duration,
+    /// How long cached season competitors stay fresh
+    pub competitors_ttl: Duration,
+    /// How long cached competitor statistics stay fresh
+    pub stats_ttl: Duration,
+    /// Maximum requests per second, defaults to the limit of the access level
+    pub rate_limit: Option<f64>,
+    /// Number of times a request is retried after a temporary failure
+    pub max_retries: u32,
+    /// Which players of the competitor statistics are added to the
+    /// [`PlayerDB`]
+    pub ingest_policy: IngestPolicy,
+}
+
+impl ClientConfig {
+    /// Create a new [`ClientConfig`] with the default values for everything
+    /// but the API key
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            api_key: ApiKey::new(api_key),
+            auth_method: AuthMethod::default(),
+            api_base_url: API_BASE_URL.to_string(),
+            access_level: ACCOUNT_ACCESS_LEVEL.to_string(),
+            cache_location: CACHE_LOCATION.to_string(),
+            cache_mode: CacheMode::Default,
+            seasons_ttl: SEASONS_CACHE_TTL,
+            competitors_ttl: COMPETITORS_CACHE_TTL,
+            stats_ttl: STATS_CACHE_TTL,
+            rate_limit: None,
+            max_retries: MAX_RETRIES,
+            ingest_policy: IngestPolicy::default(),
+        }
+    }
+
+    /// How long cached responses of an endpoint stay fresh
+    pub fn ttl<E: Endpoint>(&self, endpoint: &E) -> Duration {
+        match E::TTL {
+            CacheTtl::Seasons => self.seasons_ttl,
+            CacheTtl::Competitors => self.competitors_ttl,
+            CacheTtl::Stats => self.stats_ttl,
+            CacheTtl::Fixed(_) => endpoint.default_ttl(),
+        }
+    }
+}
+
+/// Client for the Sportradar Soccer API, caching every response it fetches.
+/// Owns the HTTP client, the API key and the cache, so callers only ask for
+/// the data they need
+pub struct SportradarClient {
+    client: Client,
+    api_endpoints: ApiEndpoints,
+    config: ClientConfig,
+    cache: Cache,
+    rate_limiter: RateLimiter,
+    retry_policy: RetryPolicy,
+    /// API key header sent with every request, when authorizing with
+    /// [`AuthMethod::Header`]
+    api_key_header: Option<HeaderValue>,
+}
+
+/// Players of a season, along with the competitors whose statistics could not
+/// be fetched
+pub struct SeasonPlayers {
+    pub player_db: PlayerDB,
+    pub failed_competitors: Vec<FailedCompetitor>,
+}
+
+/// A competitor whose statistics could not be fetched
+#[derive(Debug)]
+pub struct FailedCompetitor {
+    pub id: String,
+    pub name: String,
+    pub error: Error,
+}
+
+impl SportradarClient {
+    /// Create a new [`SportradarClient`]
+    pub fn new(config: ClientConfig) -> Result<Self> {
+        let api_endpoints = ApiEndpoints::new(&config.api_base_url, &config.access_level)?;
+
+        // Share the rate limit between every request
+        let rate_limiter = match config.rate_limit {
+            Some(requests_per_second) => {
+                RateLimiter::new(requests_per_second, requests_per_second.ceil() as u32)?
+            },
+            None => RateLimiter::for_access_level(&config.access_level),
+        };
+
+        // Validate the API key once, rather than failing every request
+        let api_key_header = match config.auth_method {
+            AuthMethod::Header => {
+                let mut api_key = HeaderValue::from_str(config.api_key.expose()).map_err(|_| {
+                    Error::Config("API key contains characters not allowed in a header".into())
+                })?;
+                api_key.set_sensitive(true);
+
+                Some(api_key)
+            },
+            AuthMethod::Query => None,
+        };
+
+        // Define headers sent with every request
+        let headers =
+            HeaderMap::from_iter([(ACCEPT, HeaderValue::from_static("application/json"))]);
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .user_agent(USER_AGENT)
+            .build()?;
+
+        Ok(Self {
+            client,
+            api_endpoints,
+            cache: Cache::new(&config.cache_location, config.cache_mode),
+            rate_limiter,
+            retry_policy: RetryPolicy::new(config.max_retries),
+            api_key_header,
+            config,
+        })
+    }
+
+    /// Get the configuration of the client
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// Get the cache the client stores responses in
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
+
+    /// Fetch the seasons of a competition
+    pub async fn competition_seasons(&self, competition_id: &str) -> Result<ApiCompetitionSeasons> {
+        self.fetch(&CompetitionSeasons { competition_id }).await
+    }
+
+    /// Fetch the competitors taking part in a season
+    pub async fn season_competitors(&self, season_id: &str) -> Result<ApiSeasonCompetitors> {
+        self.fetch(&SeasonCompetitors { season_id }).await
+    }
+
+    /// Fetch the matches of a season
+    pub async fn season_schedules(&self, season_id: &str) -> Result<ApiSeasonSchedules> {
+        self.fetch(&SeasonSchedules { season_id }).await
+    }
+
+    /// Fetch the standings of a season
+    pub async fn season_standings(&self, season_id: &str) -> Result<ApiSeasonStandings> {
+        self.fetch(&SeasonStandings { season_id }).await
+    }
+
+    /// Fetch the profile of a competitor, with the details of its players
+    pub async fn competitor_profile(&self, competitor_id: &str) -> Result<ApiCompetitorProfile> {
+        self.fetch(&CompetitorProfile { competitor_id }).await
+    }
+
+    /// Fetch the statistics of a competitor's players in a season
+    pub async fn competitor_statistics(
+        &self,
+        season_id: &str,
+        competitor_id: &str,
+    ) -> Result<ApiSeasonCompetitorStatistics> {
+        self.fetch(&CompetitorStatistics {
+            season_id,
+            competitor_id,
+        })
+        .await
+    }
+
+    /// Fetch the statistics of every competitor in the season and build the
+    /// [`PlayerDB`] from them. Competitors whose statistics can't be fetched
+    /// are skipped, unless every competitor fails
+    pub async fn season_players(&self, season_id: &str) -> Result<SeasonPlayers> {
+        let mut player_db = PlayerDB::with_ingest_policy(self.config.ingest_policy);
+
+        // get competitors for the season
+        let competitors = self.season_competitors(season_id).await?;
+
+        // get statistics for each competitor, concurrently within the rate limit
+        let competitor_statistics = join_all(
+            competitors
+                .season_competitors
+                .iter()
+                .map(|competitor| self.competitor_statistics(season_id, &competitor.id)),
+        )
+        .await;
+
+        let mut failed_competitors = Vec::new();
+
+        for (competitor, data) in competitors
+            .season_competitors
+            .iter()
+            .zip(competitor_statistics)
+        {
+            match data {
+                Ok(data) => player_db.add_competitor_statistics(&data),
+                // keep going with partial data when a competitor fails
+                Err(error) => failed_competitors.push(FailedCompetitor::new(competitor, error)),
+            }
+        }
+
+        if !failed_competitors.is_empty()
+            && failed_competitors.len() == competitors.season_competitors.len()
+        {
+            return Err(failed_competitors.remove(0).error);
+        }
+
+        Ok(SeasonPlayers {
+            player_db,
+            failed_competitors,
+        })
+    }
+
+    /// Fetch the profiles of the competitors of the players in the
+    /// [`PlayerDB`], adding their position, nationality and date of birth.
+    /// Competitors whose profile can't be fetched are returned, their players
+    /// are kept without the details
+    pub async fn add_competitor_profiles(&self, player_db: &mut PlayerDB) -> Vec<FailedCompetitor> {
+        let competitors: Vec<(String, String)> = player_db
+            .competitors()
+            .into_iter()
+            .map(|(id, name)| (id.to_string(), name.to_string()))
+            .collect();
+
+        // get profiles for each competitor, concurrently within the rate limit
+        let competitor_profiles = join_all(
+            competitors
+                .iter()
+                .map(|(competitor_id, _)| self.competitor_profile(competitor_id)),
+        )
+        .await;
+
+        let mut failed_competitors = Vec::new();
+
+        for ((id, name), data) in competitors.into_iter().zip(competitor_profiles) {
+            match data {
+                Ok(data) => player_db.add_competitor_profile(&data),
+                Err(error) => failed_competitors.push(FailedCompetitor { id, name, error }),
+            }
+        }
+
+        failed_competitors
+    }
+
+    /// Fetch the response of an [`Endpoint`], either from the cache or by
+    /// requesting it. The response is cached under the endpoint's path, and
+    /// stays fresh for the TTL configured for the endpoint
+    pub async fn fetch<E: Endpoint>(&self, endpoint: &E) -> Result<E::Response> {
+        let url = self.api_endpoints.url(endpoint)?;
+        let ttl = self.config.ttl(endpoint);
+
+        // Scope the cache by access level and the parts identifying the request
+        let cache_key = endpoint.cache_key();
+        let mut key = vec![self.config.access_level.as_str()];
+        key.extend(cache_key.iter().map(String::as_str));
+
+        let cache_path = self.cache.path(&key);
+        let cache_mode = self.cache.mode();
+
+        // Use the cached response if the cache mode and its age allow it
+        let entry = match cache_mode {
+            CacheMode::Refresh => None,
+            _ => read_from_cache::<E::Response>(&cache_path)?,
+        };
+
+        if let Lookup::Hit(data) = cached(&cache_path, entry, cache_mode, ttl)? {
+            return Ok(data);
+        }
+
+        // Request data from the API
+        let data = self.request(&url).await?;
+
+        // Write data to cache
+        let entry = CacheEntry::new(url.as_str(), data);
+        write_to_cache(&cache_path, &entry)?;
+
+        Ok(entry.data)
+    }
+
+    /// Request data from the API, retrying temporary failures according to
+    /// the [`RetryPolicy`]
+    async fn request<T>(&self, url: &Url) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        info!("Making HTTP request to {}...", redact_url(url));
+
+        let mut attempt = 0;
+
+        loop {
+            // Authorize the request with the API key
+            let request = match &self.api_key_header {
+                Some(api_key) => self
+                    .client
+                    .get(url.clone())
+                    .header(API_KEY_HEADER, api_key.clone()),
+                None => self
+                    .client
+                    .get(url.clone())
+                    .query(&[(API_KEY_QUERY_PARAM, self.config.api_key.expose())]),
+            };
+
+            // Wait for our turn to avoid the account's queries per second rate
+            // limiting
+            self.rate_limiter.acquire().await;
+
+            let err = match send_request(request, url).await {
+                Ok(data) => return Ok(data),
+                Err(err) => err,
+            };
+
+            if attempt >= self.retry_policy.max_retries || !err.is_retryable() {
+                return Err(err);
+            }
+
+            let Some(delay) = self.retry_policy.delay(attempt, err.retry_after()) else {
+                return Err(err);
+            };
+
+            attempt += 1;
+            warn!(
+                "{}. Retrying in {} ({}/{})...",
+                err,
+                humantime::format_duration(Duration::from_millis(delay.as_millis() as u64)),
+                attempt,
+                self.retry_policy.max_retries
+            );
+
+            sleep(delay).await;
+        }
+    }
+}
+
+/// Send a single request and decode its JSON response
+async fn send_request<T>(request: RequestBuilder, url: &Url) -> Result<T>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    // report errors with the url without the api key
+    let network_error = |err: reqwest::Error| Error::Network(err.with_url(url.clone()));
+
+    let response = request.send().await.map_err(network_error)?;
+
+    if !response.status().is_success() {
+        return Err(Error::from_response(&response, url.as_str()));
+    }
+
+    let body = response.bytes().await.map_err(network_error)?;
+
+    serde_json::from_slice(&body).map_err(|source| Error::Decode {
+        path: url.to_string(),
+        source,
+    })
+}
+
+impl FailedCompetitor {
+    /// Create a new [`FailedCompetitor`]
+    fn new(competitor: &ApiSeasonCompetitor, error: Error) -> Self {
+        Self {
+            id: competitor.id.clone(),
+            name: competitor.name.clone(),
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test an API key that can't be sent in a header is a configuration error
+    #[test]
+    fn test_client_invalid_api_key() {
+        let config = ClientConfig::new("key\n");
+        assert!(matches!(
+            SportradarClient::new(config),
+            Err(Error::Config(_))
+        ));
+
+        let mut config = ClientConfig::new("key\n");
+        config.auth_method = AuthMethod::Query;
+        assert!(SportradarClient::new(config).is_ok());
+    }
+}
@@ -0,0 +1,394 @@
+use std::{
+    collections::BTreeMap,
+    env,
+    error::Error,
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use serde::Deserialize;
+
+/// Name of the project-local config file, read from the working directory
+pub const CONFIG_FILE_NAME: &str = "talent-scout.toml";
+
+/// Path of the user-level config file, under `$XDG_CONFIG_HOME`
+pub const USER_CONFIG_PATH: &str = "talent-scout/config.toml";
+
+/// Settings of a config file, named after their flag. Top-level settings are
+/// overridden by the named profiles of the `[profile.<name>]` tables
+///
+/// ```toml
+/// competition_id = ["sr:competition:17", "sr:competition:8"]
+///
+/// [profile.prod]
+/// access_level = "production"
+/// rate_limit = 10
+/// ```
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    pub api_key: Option<String>,
+    pub auth: Option<String>,
+    pub api_base_url: Option<String>,
+    pub access_level: Option<String>,
+    pub rate_limit: Option<f64>,
+    pub max_retries: Option<u32>,
+    pub competition_id: Option<Competitions>,
+    pub season: Option<String>,
+    pub cache_location: Option<String>,
+    pub profiles: Option<bool>,
+    pub players: Option<String>,
+    pub seasons_ttl: Option<String>,
+    pub competitors_ttl: Option<String>,
+    pub stats_ttl: Option<String>,
+    pub limit: Option<usize>,
+    pub ties: Option<String>,
+    pub min_minutes: Option<u16>,
+    pub per_competition: Option<bool>,
+    pub format: Option<String>,
+    /// Named profiles, only allowed at the top level
+    pub profile: BTreeMap<String, Settings>,
+}
+
+/// Competitions of the `competition_id` setting, either a single string,
+/// which may hold several competitions separated by commas, or an array
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Competitions {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// A config file, along with the path it was read from
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub settings: Settings,
+}
+
+/// Config files layered on top of each other, later files override earlier
+/// ones
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub files: Vec<ConfigFile>,
+}
+
+impl Competitions {
+    /// Every competition listed by the setting
+    pub fn to_vec(&self) -> Vec<&str> {
+        match self {
+            Competitions::One(competitions) => competitions.split(',').collect(),
+            Competitions::Many(competitions) => competitions.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl ConfigFile {
+    /// Read and parse a config file, returns `None` when it doesn't exist
+    pub fn load(path: &Path) -> Result<Option<Self>, ConfigError> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(source) => {
+                return Err(ConfigError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            },
+        };
+
+        debug!("Reading config file at: {}", path.display());
+
+        Self::parse(path, &contents).map(Some)
+    }
+
+    /// Parse the contents of a config file read from `path`
+    pub fn parse(path: &Path, contents: &str) -> Result<Self, ConfigError> {
+        let settings: Settings = toml::from_str(contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        if let Some(name) = settings
+            .profile
+            .iter()
+            .find(|(_, profile)| !profile.profile.is_empty())
+            .map(|(name, _)| name)
+        {
+            return Err(ConfigError::Setting {
+                path: path.to_path_buf(),
+                key: format!("profile.{}.profile", name),
+                message: "profiles can not be nested".to_string(),
+            });
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            settings,
+        })
+    }
+}
+
+impl Config {
+    /// Paths the config files are read from, the user-level file and then the
+    /// project-local one, which overrides it
+    pub fn default_paths() -> Vec<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+
+        config_home
+            .map(|config_home| config_home.join(USER_CONFIG_PATH))
+            .into_iter()
+            .chain([PathBuf::from(CONFIG_FILE_NAME)])
+            .collect()
+    }
+
+    /// Load the config files that exist out of `paths`
+    pub fn load(paths: &[PathBuf]) -> Result<Self, ConfigError> {
+        let mut files = Vec::new();
+
+        for path in paths {
+            files.extend(ConfigFile::load(path)?);
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Names of the profiles defined in any of the files
+    pub fn profiles(&self) -> Vec<&str> {
+        let mut profiles: Vec<&str> = self
+            .files
+            .iter()
+            .flat_map(|file| file.settings.profile.keys().map(String::as_str))
+            .collect();
+
+        profiles.sort();
+        profiles.dedup();
+
+        profiles
+    }
+
+    /// Settings of the files from the lowest to the highest precedence, along
+    /// with the file they were read from: the top-level settings of every
+    /// file first and then the settings of the `profile` in every file
+    pub fn layers(&self, profile: Option<&str>) -> Result<Vec<(&Settings, &Path)>, ConfigError> {
+        let mut layers: Vec<(&Settings, &Path)> = self
+            .files
+            .iter()
+            .map(|file| (&file.settings, file.path.as_path()))
+            .collect();
+
+        let Some(profile) = profile else {
+            return Ok(layers);
+        };
+
+        let profile_layers: Vec<(&Settings, &Path)> = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                let settings = file.settings.profile.get(profile)?;
+
+                Some((settings, file.path.as_path()))
+            })
+            .collect();
+
+        if profile_layers.is_empty() {
+            return Err(ConfigError::ProfileNotFound {
+                profile: profile.to_string(),
+                available: self.profiles().iter().map(|p| p.to_string()).collect(),
+            });
+        }
+
+        layers.extend(profile_layers);
+
+        Ok(layers)
+    }
+}
+
+/// Errors returned when reading the config files
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A config file exists but could not be read
+    Io { path: PathBuf, source: io::Error },
+    /// A config file is not valid TOML or has unknown settings or values of
+    /// the wrong type
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// A setting of a config file has an invalid value
+    Setting {
+        path: PathBuf,
+        key: String,
+        message: String,
+    },
+    /// The selected profile isn't defined in any config file
+    ProfileNotFound {
+        profile: String,
+        available: Vec<String>,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, .. } => {
+                write!(f, "Failed to read config file {}", path.display())
+            },
+            ConfigError::Parse { path, .. } => {
+                write!(f, "Invalid config file {}", path.display())
+            },
+            ConfigError::Setting { path, key, message } => {
+                write!(f, "{}: invalid `{}`: {}", path.display(), key, message)
+            },
+            ConfigError::ProfileNotFound { profile, available } if available.is_empty() => write!(
+                f,
+                "Profile \"{}\" not found, no config file defines profiles",
+                profile
+            ),
+            ConfigError::ProfileNotFound { profile, available } => write!(
+                f,
+                "Profile \"{}\" not found, available profiles: {}",
+                profile,
+                available.join(", ")
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Io { source, .. } => Some(source),
+            ConfigError::Parse { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(path: &str, contents: &str) -> ConfigFile {
+        ConfigFile::parse(Path::new(path), contents).unwrap()
+    }
+
+    // test settings and profiles are deserialized from TOML
+    #[test]
+    fn test_parse_config_file() {
+        let file = parse(
+            "config.toml",
+            r#"
+            # shared by every profile
+            competition_id = ["sr:competition:17", "sr:competition:8"]
+            season = '23/24'
+            rate_limit = 1_000
+            profiles = true
+
+            [profile.prod]
+            access_level = "production"
+            api_key = """\
+            a "quoted" key # not a comment"""
+
+            [profile."trial account"]
+            max_retries = 5
+            competition_id = "sr:competition:17,sr:competition:8"
+            "#,
+        );
+
+        let settings = &file.settings;
+        assert_eq!(
+            settings.competition_id.as_ref().unwrap().to_vec(),
+            ["sr:competition:17", "sr:competition:8"]
+        );
+        assert_eq!(settings.season.as_deref(), Some("23/24"));
+        assert_eq!(settings.rate_limit, Some(1000.0));
+        assert_eq!(settings.profiles, Some(true));
+        assert_eq!(settings.limit, None);
+
+        let prod = &settings.profile["prod"];
+        assert_eq!(prod.access_level.as_deref(), Some("production"));
+        assert_eq!(
+            prod.api_key.as_deref(),
+            Some("a \"quoted\" key # not a comment")
+        );
+
+        let trial = &settings.profile["trial account"];
+        assert_eq!(trial.max_retries, Some(5));
+        assert_eq!(
+            trial.competition_id.as_ref().unwrap().to_vec(),
+            ["sr:competition:17", "sr:competition:8"]
+        );
+    }
+
+    // test unknown settings and nested profiles are rejected
+    #[test]
+    fn test_parse_config_file_errors() {
+        let error = |contents| ConfigFile::parse(Path::new("config.toml"), contents).unwrap_err();
+
+        assert!(matches!(
+            error("stats = \"goals\""),
+            ConfigError::Parse { .. }
+        ));
+        assert!(matches!(
+            error("limit = \"ten\""),
+            ConfigError::Parse { .. }
+        ));
+        assert_eq!(
+            error("[profile.prod.profile.dev]\nlimit = 3").to_string(),
+            "config.toml: invalid `profile.prod.profile`: profiles can not be nested"
+        );
+    }
+
+    // test later files and profiles override the earlier settings
+    #[test]
+    fn test_config_layers() {
+        let config = Config {
+            files: vec![
+                parse(
+                    "user.toml",
+                    "access_level = \"trial\"\nlimit = 5\n[profile.prod]\naccess_level = \"production\"",
+                ),
+                parse(
+                    "project.toml",
+                    "limit = 20\naccess_level = \"trial\"\n[profile.dev]\nlimit = 3",
+                ),
+            ],
+        };
+
+        let paths = |layers: Vec<(&Settings, &Path)>| -> Vec<PathBuf> {
+            layers.iter().map(|(_, path)| path.to_path_buf()).collect()
+        };
+
+        assert_eq!(
+            paths(config.layers(None).unwrap()),
+            [Path::new("user.toml"), Path::new("project.toml")]
+        );
+
+        // profiles override every file's top-level settings
+        let layers = config.layers(Some("prod")).unwrap();
+        assert_eq!(
+            layers.last().unwrap().0.access_level.as_deref(),
+            Some("production")
+        );
+        assert_eq!(
+            paths(layers),
+            [
+                Path::new("user.toml"),
+                Path::new("project.toml"),
+                Path::new("user.toml")
+            ]
+        );
+
+        assert_eq!(
+            config.layers(Some("staging")).unwrap_err().to_string(),
+            "Profile \"staging\" not found, available profiles: dev, prod"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use std::{fmt, io, time::Duration};
+
+use reqwest::{header::RETRY_AFTER, Response, StatusCode};
+
+use crate::retry::parse_retry_after;
+
+/// Result type returned when fetching data from the API
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors returned when fetching data from the API
+#[derive(Debug)]
+pub enum Error {
+    /// The API key is missing, invalid or not allowed to access the resource
+    Auth { status: StatusCode },
+    /// The account's queries per second or monthly quota was exceeded
+    RateLimited { retry_after: Option<Duration> },
+    /// The requested resource does not exist
+    NotFound { resource: String },
+    /// The API responded with any other unsuccessful status
+    Status {
+        status: StatusCode,
+        resource: String,
+    },
+    /// A response is not valid JSON for the expected type
+    Decode {
+        path: String,
+        source: serde_json::Error,
+    },
+    /// The cache could not be read or written
+    Cache { path: String, source: io::Error },
+    /// The request could not be sent or the response could not be received
+    Network(reqwest::Error),
+    /// The URL of a request could not be built
+    Url(url::ParseError),
+    /// The client was configured with an invalid value
+    Config(String),
+}
+
+impl Error {
+    /// Create an [`Error`] from an unsuccessful response to a request for
+    /// `resource`
+    pub fn from_response(response: &Response, resource: &str) -> Self {
+        match response.status() {
+            status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => Error::Auth { status },
+            StatusCode::TOO_MANY_REQUESTS => Error::RateLimited {
+                retry_after: response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after),
+            },
+            StatusCode::NOT_FOUND => Error::NotFound {
+                resource: resource.to_string(),
+            },
+            status => Error::Status {
+                status,
+                resource: resource.to_string(),
+            },
+        }
+    }
+
+    /// Check if the request is worth retrying. Rate limits, timeouts and
+    /// server errors are temporary, while authentication errors or missing
+    /// resources will fail again
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited { .. } => true,
+            // 429 is always mapped to `RateLimited`
+            Error::Status { status, .. } => {
+                *status == StatusCode::REQUEST_TIMEOUT || status.is_server_error()
+            },
+            Error::Network(err) => err.is_timeout() || err.is_connect() || err.is_request(),
+            _ => false,
+        }
+    }
+
+    /// How long the API asked us to wait before retrying
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Auth { status } => write!(
+                f,
+                "Request was not authorized ({}), check the API key and access level",
+                status
+            ),
+            Error::RateLimited {
+                retry_after: Some(retry_after),
+            } => write!(
+                f,
+                "Rate limit exceeded, retry after {}s",
+                retry_after.as_secs()
+            ),
+            Error::RateLimited { retry_after: None } => write!(f, "Rate limit exceeded"),
+            Error::NotFound { resource } => write!(f, "Resource not found: {}", resource),
+            Error::Status { status, resource } => {
+                write!(f, "Request failed with status: {} ({})", status, resource)
+            },
+            Error::Decode { path, .. } => write!(f, "Failed to decode JSON from {}", path),
+            Error::Cache { path, .. } => write!(f, "Failed to access the cache at {}", path),
+            Error::Network(_) => write!(f, "Failed to send request"),
+            Error::Url(_) => write!(f, "Failed to build the request URL"),
+            Error::Config(message) => write!(f, "Invalid client configuration: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Decode { source, .. } => Some(source),
+            Error::Cache { source, .. } => Some(source),
+            Error::Network(source) => Some(source),
+            Error::Url(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Network(err)
+    }
+}
+
+impl From<url::ParseError> for Error {
+    fn from(err: url::ParseError) -> Self {
+        Error::Url(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test only temporary errors are retried
+    #[test]
+    fn test_error_is_retryable() {
+        let rate_limited = Error::RateLimited {
+            retry_after: Some(Duration::from_secs(2)),
+        };
+
+        assert!(rate_limited.is_retryable());
+        assert_eq!(rate_limited.retry_after(), Some(Duration::from_secs(2)));
+
+        assert!(Error::Status {
+            status: StatusCode::BAD_GATEWAY,
+            resource: "seasons.json".into(),
+        }
+        .is_retryable());
+
+        assert!(Error::Status {
+            status: StatusCode::REQUEST_TIMEOUT,
+            resource: "seasons.json".into(),
+        }
+        .is_retryable());
+        assert!(!Error::Status {
+            status: StatusCode::BAD_REQUEST,
+            resource: "seasons.json".into(),
+        }
+        .is_retryable());
+
+        assert!(!Error::Auth {
+            status: StatusCode::UNAUTHORIZED,
+        }
+        .is_retryable());
+        assert!(!Error::NotFound {
+            resource: "seasons.json".into(),
+        }
+        .is_retryable());
+    }
+}
@@ -0,0 +1,322 @@
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::api::{ApiSchedule, ApiSeasonSchedules, ApiSportEventCompetitor};
+
+/// A match of a season, either played or upcoming
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub id: String,
+    pub start_time: DateTime<Utc>,
+    pub home: FixtureCompetitor,
+    pub away: FixtureCompetitor,
+    pub venue: Option<String>,
+    /// `not_started`, `live`, `closed`, `ended`, `postponed`, `cancelled`...
+    pub status: String,
+}
+
+/// A competitor of a [`Fixture`], with its score once the match started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureCompetitor {
+    pub id: String,
+    pub name: String,
+    pub score: Option<u16>,
+}
+
+/// Where a [`Fixture`] is in its lifecycle, grouping the many statuses of the
+/// API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FixtureState {
+    /// The match is over, `closed` or `ended`
+    Played,
+    /// The match started and is not over yet, including interruptions
+    Live,
+    /// The match has not started yet, `not_started` or `delayed`
+    Upcoming,
+    /// The match won't be played as scheduled, `postponed`, `cancelled` or
+    /// `abandoned`
+    Postponed,
+}
+
+/// Filters the fixtures of a season by team and date range
+#[derive(Debug, Clone, Default)]
+pub struct FixtureFilter {
+    /// Competitor id or part of its name, ignoring case
+    pub team: Option<String>,
+    /// First day of the range, inclusive
+    pub from: Option<NaiveDate>,
+    /// Last day of the range, inclusive
+    pub to: Option<NaiveDate>,
+}
+
+impl Fixture {
+    /// Create a new [`Fixture`] from a match of the season schedules
+    pub fn new(schedule: &ApiSchedule) -> Self {
+        let sport_event = &schedule.sport_event;
+        let status = &schedule.sport_event_status;
+
+        // the API marks which competitor plays at home, fall back to the order
+        let competitor = |qualifier: &str, index: usize| {
+            sport_event
+                .competitors
+                .iter()
+                .find(|c| c.qualifier.as_deref() == Some(qualifier))
+                .or_else(|| sport_event.competitors.get(index))
+        };
+
+        Self {
+            id: sport_event.id.clone(),
+            start_time: sport_event.start_time,
+            home: FixtureCompetitor::new(competitor("home", 0), status.home_score),
+            away: FixtureCompetitor::new(competitor("away", 1), status.away_score),
+            venue: sport_event.venue.as_ref().map(|venue| venue.name.clone()),
+            status: status.status.clone(),
+        }
+    }
+
+    /// Get the [`FixtureState`] of the match from its status
+    pub fn state(&self) -> FixtureState {
+        match self.status.as_str() {
+            "closed" | "ended" => FixtureState::Played,
+            "not_started" | "match_about_to_start" | "delayed" => FixtureState::Upcoming,
+            "postponed" | "cancelled" | "abandoned" => FixtureState::Postponed,
+            _ => FixtureState::Live,
+        }
+    }
+
+    /// Check if a team plays in the match, by its id or part of its name
+    pub fn involves(&self, team: &str) -> bool {
+        let team = team.trim().to_lowercase();
+
+        [&self.home, &self.away]
+            .iter()
+            .any(|c| c.id.to_lowercase() == team || c.name.to_lowercase().contains(&team))
+    }
+}
+
+impl fmt::Display for Fixture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} | ", self.start_time.format("%Y-%m-%d %H:%M"))?;
+
+        match (self.home.score, self.away.score) {
+            (Some(home_score), Some(away_score)) => write!(
+                f,
+                "{} {} - {} {}",
+                self.home.name, home_score, away_score, self.away.name
+            )?,
+            _ => write!(f, "{} vs {}", self.home.name, self.away.name)?,
+        }
+
+        if let Some(venue) = &self.venue {
+            write!(f, " | {}", venue)?;
+        }
+
+        // only mention the status when it is not the usual one
+        if !matches!(self.status.as_str(), "closed" | "ended" | "not_started") {
+            write!(f, " ({})", self.status)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FixtureState {
+    /// Every state, in the order the fixtures are listed
+    pub const ALL: [FixtureState; 4] = [
+        FixtureState::Played,
+        FixtureState::Live,
+        FixtureState::Upcoming,
+        FixtureState::Postponed,
+    ];
+
+    /// Title of the fixtures in the state
+    pub fn title(&self) -> &'static str {
+        match self {
+            FixtureState::Played => "Played",
+            FixtureState::Live => "Live",
+            FixtureState::Upcoming => "Upcoming",
+            FixtureState::Postponed => "Postponed or cancelled",
+        }
+    }
+}
+
+impl FixtureCompetitor {
+    /// Create a new [`FixtureCompetitor`], unknown until the draw when the
+    /// competitor is missing
+    fn new(competitor: Option<&ApiSportEventCompetitor>, score: Option<u16>) -> Self {
+        match competitor {
+            Some(competitor) => Self {
+                id: competitor.id.clone(),
+                name: competitor.name.clone(),
+                score,
+            },
+            None => Self {
+                id: String::new(),
+                name: "TBD".to_string(),
+                score,
+            },
+        }
+    }
+}
+
+impl FixtureFilter {
+    /// Check if a fixture matches the filter
+    pub fn matches(&self, fixture: &Fixture) -> bool {
+        let date = fixture.start_time.date_naive();
+
+        self.team.as_ref().is_none_or(|team| fixture.involves(team))
+            && self.from.is_none_or(|from| from <= date)
+            && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+/// Get the fixtures of a season matching the filter, in the order they are
+/// played
+pub fn season_fixtures(schedules: &ApiSeasonSchedules, filter: &FixtureFilter) -> Vec<Fixture> {
+    let mut fixtures: Vec<Fixture> = schedules
+        .schedules
+        .iter()
+        .map(Fixture::new)
+        .filter(|fixture| filter.matches(fixture))
+        .collect();
+
+    fixtures.sort_by(|a, b| {
+        a.start_time
+            .cmp(&b.start_time)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+
+    fixtures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedules() -> ApiSeasonSchedules {
+        serde_json::from_str(
+            r#"{"schedules": [
+                {
+                    "sport_event": {
+                        "id": "sr:sport_event:2",
+                        "start_time": "2023-08-12T14:00:00+00:00",
+                        "competitors": [
+                            {"id": "sr:competitor:42", "name": "Arsenal FC", "qualifier": "home"},
+                            {"id": "sr:competitor:37", "name": "Nottingham Forest", "qualifier": "away"}
+                        ],
+                        "venue": {"id": "sr:venue:1", "name": "Emirates Stadium"}
+                    },
+                    "sport_event_status": {"status": "closed", "home_score": 2, "away_score": 1}
+                },
+                {
+                    "sport_event": {
+                        "id": "sr:sport_event:1",
+                        "start_time": "2023-08-11T19:00:00+00:00",
+                        "competitors": [
+                            {"id": "sr:competitor:17", "name": "Manchester City", "qualifier": "away"},
+                            {"id": "sr:competitor:6", "name": "Burnley FC", "qualifier": "home"}
+                        ]
+                    },
+                    "sport_event_status": {"status": "closed", "home_score": 0, "away_score": 3}
+                },
+                {
+                    "sport_event": {
+                        "id": "sr:sport_event:3",
+                        "start_time": "2024-05-19T15:00:00+00:00",
+                        "competitors": [
+                            {"id": "sr:competitor:17", "name": "Manchester City", "qualifier": "home"},
+                            {"id": "sr:competitor:3", "name": "West Ham United", "qualifier": "away"}
+                        ]
+                    },
+                    "sport_event_status": {"status": "not_started"}
+                }
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    fn ids(fixtures: &[Fixture]) -> Vec<&str> {
+        fixtures.iter().map(|f| f.id.as_str()).collect()
+    }
+
+    // test fixtures are sorted by start time and split into played and upcoming
+    #[test]
+    fn test_season_fixtures() {
+        let fixtures = season_fixtures(&schedules(), &FixtureFilter::default());
+
+        assert_eq!(
+            ids(&fixtures),
+            ["sr:sport_event:1", "sr:sport_event:2", "sr:sport_event:3"]
+        );
+        assert_eq!(fixtures[0].state(), FixtureState::Played);
+        assert_eq!(fixtures[2].state(), FixtureState::Upcoming);
+
+        assert_eq!(
+            fixtures[0].to_string(),
+            "2023-08-11 19:00 | Burnley FC 0 - 3 Manchester City"
+        );
+        assert_eq!(
+            fixtures[1].to_string(),
+            "2023-08-12 14:00 | Arsenal FC 2 - 1 Nottingham Forest | Emirates Stadium"
+        );
+        assert_eq!(
+            fixtures[2].to_string(),
+            "2024-05-19 15:00 | Manchester City vs West Ham United"
+        );
+    }
+
+    // test live, postponed and cancelled matches are not listed as upcoming
+    #[test]
+    fn test_fixture_state() {
+        let mut fixture = season_fixtures(&schedules(), &FixtureFilter::default()).remove(2);
+
+        for (status, state) in [
+            ("ended", FixtureState::Played),
+            ("live", FixtureState::Live),
+            ("interrupted", FixtureState::Live),
+            ("delayed", FixtureState::Upcoming),
+            ("postponed", FixtureState::Postponed),
+            ("cancelled", FixtureState::Postponed),
+        ] {
+            fixture.status = status.to_string();
+            assert_eq!(fixture.state(), state, "{}", status);
+        }
+
+        assert_eq!(
+            fixture.to_string(),
+            "2024-05-19 15:00 | Manchester City vs West Ham United (cancelled)"
+        );
+    }
+
+    // test fixtures are filtered by team and date range
+    #[test]
+    fn test_fixture_filter() {
+        let by_name = FixtureFilter {
+            team: Some("manchester city".to_string()),
+            ..Default::default()
+        };
+        let by_id = FixtureFilter {
+            team: Some("sr:competitor:42".to_string()),
+            ..Default::default()
+        };
+        let by_date = FixtureFilter {
+            team: Some("City".to_string()),
+            from: NaiveDate::from_ymd_opt(2024, 1, 1),
+            to: NaiveDate::from_ymd_opt(2024, 5, 19),
+        };
+
+        assert_eq!(
+            ids(&season_fixtures(&schedules(), &by_name)),
+            ["sr:sport_event:1", "sr:sport_event:3"]
+        );
+        assert_eq!(
+            ids(&season_fixtures(&schedules(), &by_id)),
+            ["sr:sport_event:2"]
+        );
+        assert_eq!(
+            ids(&season_fixtures(&schedules(), &by_date)),
+            ["sr:sport_event:3"]
+        );
+    }
+}
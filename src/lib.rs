@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+pub mod api;
+pub mod auth;
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod fixture;
+pub mod output;
+pub mod player;
+pub mod rate_limit;
+pub mod retry;
+pub mod season;
+pub mod standings;
+pub mod utils;
+
+pub use client::{ClientConfig, SportradarClient};
+pub use player::PlayerDB;
+
+// Default values for environment variables
+pub const API_BASE_URL: &str = "https://api.sportradar.com";
+pub const COMPETITION_ID: &str = "sr:competition:17";
+pub const CACHE_LOCATION: &str = "cache";
+pub const ACCOUNT_ACCESS_LEVEL: &str = "trial";
+pub const MAX_RETRIES: u32 = 3;
+
+// Default time cached responses stay fresh for
+pub const SEASONS_CACHE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+pub const COMPETITORS_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+pub const STATS_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
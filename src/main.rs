@@ -1,142 +1,785 @@
-use std::{collections::HashMap, env, fs};
-
-use anyhow::{Context, Result};
-use api::{
-    ApiCompetitionSeasons,
-    ApiEndpoints,
-    ApiSeasonCompetitorStatistics,
-    ApiSeasonCompetitors,
+use std::{fs, path::Path, process::ExitCode, time::Duration};
+
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use clap::{CommandFactory, FromArgMatches};
+use cli::{
+    ApiArgs,
+    ApiCommand,
+    CacheArgs,
+    CacheCommand,
+    Cli,
+    Command,
+    ConfigCommand,
+    LeaderboardArgs,
+    SETTINGS,
+};
+use log::{warn, LevelFilter};
+use serde_json::Value;
+use talent_scout::{
+    api::{ApiCompetitionSeason, ApiCompetitionSeasons},
+    client::FailedCompetitor,
+    error::Error,
+    fixture::{season_fixtures, FixtureFilter, FixtureState},
+    output::Output,
+    player::{PlayerDB, PlayerNotFound, PlayerStatistics, StatKey, LEADERBOARD_COLUMNS},
+    season::{CompetitionSelector, SeasonNotFound, SeasonSelector},
+    standings::{standing_groups, standings_table},
+    utils::{clear_cache, list_cache_files, read_cache_entry_info},
+    ClientConfig,
+    SportradarClient,
 };
-use player::{Player, PlayerDB};
-use reqwest::Client;
-use utils::fetch_data;
 
-pub mod api;
-pub mod player;
-pub mod utils;
+mod cli;
 
-// Default values for environment variables
-pub const API_BASE_URL: &str = "https://api.sportradar.com";
-pub const COMPETITION_ID: &str = "sr:competition:17";
-pub const CACHE_LOCATION: &str = "cache";
-pub const ACCOUNT_ACCESS_LEVEL: &str = "trial";
+// Process exit codes for each kind of error
+const EXIT_FAILURE: u8 = 1;
+const EXIT_AUTH: u8 = 3;
+const EXIT_RATE_LIMITED: u8 = 4;
+const EXIT_NOT_FOUND: u8 = 5;
+const EXIT_NETWORK: u8 = 6;
+const EXIT_DECODE: u8 = 7;
+const EXIT_CACHE: u8 = 8;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    // Create an HTTP client
-    let client = Client::new();
+async fn main() -> ExitCode {
+    match run().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
 
-    let mut player_db: PlayerDB = PlayerDB::new();
+            ExitCode::from(exit_code(&err))
+        },
+    }
+}
 
-    let api_endpoints = ApiEndpoints::new()?;
+/// Map an error to the process exit code, so scripts can tell an invalid API
+/// key from a missing season or a rate limit
+fn exit_code(err: &anyhow::Error) -> u8 {
+    for cause in err.chain() {
+        if let Some(err) = cause.downcast_ref::<Error>() {
+            return match err {
+                Error::Auth { .. } => EXIT_AUTH,
+                Error::RateLimited { .. } => EXIT_RATE_LIMITED,
+                Error::NotFound { .. } => EXIT_NOT_FOUND,
+                Error::Status { .. } | Error::Network(_) => EXIT_NETWORK,
+                Error::Decode { .. } => EXIT_DECODE,
+                Error::Cache { .. } => EXIT_CACHE,
+                Error::Url(_) | Error::Config(_) => EXIT_FAILURE,
+            };
+        }
 
-    // Set up environment variables
-    let api_key = env::var("API_KEY").context("API_KEY environment variable not found")?;
-    let competition_id = env::var("COMPETITION_ID").unwrap_or_else(|_| COMPETITION_ID.to_string());
-    let cache_location = env::var("CACHE_LOCATION").unwrap_or_else(|_| CACHE_LOCATION.to_string());
-    let access_level =
-        env::var("ACCOUNT_ACCESS_LEVEL").unwrap_or_else(|_| ACCOUNT_ACCESS_LEVEL.into());
+        if cause.is::<SeasonNotFound>() || cause.is::<PlayerNotFound>() {
+            return EXIT_NOT_FOUND;
+        }
+    }
 
-    // Define query parameters for the request authorization
-    let query_params = HashMap::from([("api_key", api_key.as_str())]);
+    EXIT_FAILURE
+}
 
-    // Define headers for the request
-    let headers = HashMap::from([("Accept", "application/json"), ("User-Agent", "reqwest")]);
+/// Create the [`SportradarClient`] from the command line arguments
+fn create_client(args: &ApiArgs, cache: &CacheArgs) -> Result<SportradarClient> {
+    let api_key = args
+        .api_key
+        .clone()
+        .context("API key not provided, use --api-key or the API_KEY environment variable")?;
 
     // Ensure cache directory exists
-    fs::create_dir_all(&cache_location).context("Failed to create cache directory")?;
-
-    let competition_seasons_url = api_endpoints.competition_seasons(competition_id.as_str())?;
-    let competition_seasons_cache_path = &format!("{}/competition_seasons.json", cache_location);
-
-    // 1. call the API to get the season id from the competition_seasons
-    let competition_seasons = fetch_data::<ApiCompetitionSeasons>(
-        &client,
-        competition_seasons_url.as_str(),
-        &query_params,
-        &headers,
-        competition_seasons_cache_path,
-        access_level.as_str(),
-    )
-    .await
-    .context("Failed to fetch competition seasons")?;
-
-    // extract the season id for the Premier League 23/24
-    let season = competition_seasons
-        .seasons
-        .iter()
-        .find(|s| s.name == "Premier League 23/24")
-        .expect("Season not found");
-
-    let season_competitors_url = api_endpoints.season_competitors(&season.id)?;
-    let competitors_cache_path = &format!("{}/season_competitors.json", cache_location);
-
-    // 2. get competitors from for the season
-    let competitors = fetch_data::<ApiSeasonCompetitors>(
-        &client,
-        season_competitors_url.as_str(),
-        &query_params,
-        &headers,
-        competitors_cache_path,
-        access_level.as_str(),
-    )
-    .await
-    .context("Failed to fetch season competitors")?;
-
-    // 3. get statistics for each competitor
-    for competitor in competitors.season_competitors.iter() {
-        let competitor_statistics_url =
-            api_endpoints.competitor_statistics(&season.id, &competitor.id)?;
-
-        let stats_cache_path = &format!("{}/stats_{}.json", cache_location, competitor.id);
-
-        // fetch data for each competitor
-        match fetch_data::<ApiSeasonCompetitorStatistics>(
-            &client,
-            competitor_statistics_url.as_str(),
-            &query_params,
-            &headers,
-            stats_cache_path,
-            access_level.as_str(),
-        )
-        .await
-        {
-            Ok(data) => {
-                // skip competitors with no goals
-                if data.competitor.players.is_empty() {
+    fs::create_dir_all(&args.cache_location).context("Failed to create cache directory")?;
+
+    let config = ClientConfig {
+        api_key,
+        auth_method: args.auth,
+        api_base_url: args.api_base_url.clone(),
+        access_level: args.access_level.clone(),
+        cache_location: args.cache_location.clone(),
+        cache_mode: cache.mode(),
+        seasons_ttl: cache.seasons_ttl.into(),
+        competitors_ttl: cache.competitors_ttl.into(),
+        stats_ttl: cache.stats_ttl.into(),
+        rate_limit: args.rate_limit,
+        max_retries: args.max_retries,
+        ingest_policy: args.players,
+    };
+
+    SportradarClient::new(config).context("Failed to create the API client")
+}
+
+/// Run the command selected on the command line and print its output
+async fn run() -> Result<()> {
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+
+    // diagnostics go to stderr, leaving stdout for the output. Dependencies
+    // only log warnings, RUST_LOG can still tune the level of each module
+    let log_level = cli.log_level();
+
+    env_logger::Builder::new()
+        .filter_level(log_level.min(LevelFilter::Warn))
+        .filter_module("talent_scout", log_level)
+        .format_timestamp(None)
+        .format_target(false)
+        .parse_default_env()
+        .init();
+
+    // settings missing from the flags and environment come from the config files
+    cli.load_config(&matches)
+        .context("Failed to load the config files")?;
+
+    let command = cli.command.take().unwrap_or_default();
+
+    // the cache and config commands work without the API key
+    let output = match command {
+        Command::Cache { command } => run_cache_command(&command, &cli.api.cache_location)?,
+        Command::Config { command } => run_config_command(&command, &cli),
+        Command::Api(command) => {
+            let client = create_client(&cli.api, &cli.cache)?;
+
+            run_command(command, &client, &cli.api, &cli.leaderboard).await?
+        },
+    };
+
+    print!("{}", output.render(cli.format));
+
+    Ok(())
+}
+
+/// Run a command using the API
+async fn run_command(
+    command: ApiCommand,
+    client: &SportradarClient,
+    args: &ApiArgs,
+    leaderboard: &LeaderboardArgs,
+) -> Result<Output> {
+    let output = match command {
+        ApiCommand::Leaders => {
+            leaderboards_output(
+                client,
+                args,
+                leaderboard,
+                &[StatKey::Goals, StatKey::Assists],
+            )
+            .await?
+        },
+        ApiCommand::TopScorers => {
+            leaderboards_output(client, args, leaderboard, &[StatKey::Goals]).await?
+        },
+        ApiCommand::TopAssists => {
+            leaderboards_output(client, args, leaderboard, &[StatKey::Assists]).await?
+        },
+        ApiCommand::Top { stats } => leaderboards_output(client, args, leaderboard, &stats).await?,
+        ApiCommand::Efficiency { metrics } => {
+            let mut output = Output::new(LEADERBOARD_COLUMNS);
+
+            for (heading, player_db) in leaderboard_dbs(client, args, leaderboard).await? {
+                push_heading(&mut output, heading);
+
+                for metric in metrics.iter() {
+                    let metric_leaderboard = player_db.metric_leaderboard(
+                        *metric,
+                        leaderboard.limit,
+                        leaderboard.ties,
+                        leaderboard.min_minutes,
+                    );
+
+                    player_db.write_leaderboard(&metric_leaderboard, &mut output);
+                }
+            }
+
+            output
+        },
+        ApiCommand::History { player } => {
+            let mut player_db = PlayerDB::with_ingest_policy(args.players);
+
+            for (_, season_db) in load_player_dbs(client, args).await? {
+                player_db.extend(season_db);
+            }
+
+            let mut output = Output::new(&[
+                "player_id",
+                "player",
+                "position",
+                "nationality",
+                "date_of_birth",
+                "season_id",
+                "season",
+                "team_id",
+                "team",
+                "matches_played",
+                "minutes_played",
+                "goals",
+                "assists",
+            ]);
+
+            for player in player_db.find_players(&player)? {
+                let mut heading = format!("{} ({})", player.name, player.id);
+                let details = player.details();
+
+                if !details.is_empty() {
+                    heading.push_str(&format!(" [{}]", details.join(", ")));
+                }
+
+                output.push_text(format!("{}:", heading));
+
+                // the columns identifying the player, repeated on every row
+                let player_columns = || -> Vec<Value> {
+                    vec![
+                        player.id.clone().into(),
+                        player.name.clone().into(),
+                        player.position.clone().into(),
+                        player.nationality.clone().into(),
+                        player.date_of_birth.map(|date| date.to_string()).into(),
+                    ]
+                };
+
+                for stint in player.stints.iter() {
+                    output.push_text(format!(
+                        "{} | {} | {}",
+                        stint.season_name,
+                        stint.competitor_name,
+                        history_stats(stint.goals_scored, stint.assists, &stint.statistics)
+                    ));
+                    output.push_row(
+                        player_columns()
+                            .into_iter()
+                            .chain([
+                                stint.season_id.clone().into(),
+                                stint.season_name.clone().into(),
+                                stint.competitor_id.clone().into(),
+                                stint.competitor_name.clone().into(),
+                                stint.statistics.matches_played.into(),
+                                stint.statistics.minutes_played.into(),
+                                stint.goals_scored.into(),
+                                stint.assists.into(),
+                            ])
+                            .collect(),
+                    );
+                }
+
+                output.push_text(format!(
+                    "Total | {}",
+                    history_stats(player.goals_scored, player.assists, &player.statistics)
+                ));
+                output.push_text("");
+
+                // the totals over every season, for scripts
+                output.push_row(
+                    player_columns()
+                        .into_iter()
+                        .chain([
+                            "total".into(),
+                            "total".into(),
+                            Value::Null,
+                            player.teams().join(", ").into(),
+                            player.statistics.matches_played.into(),
+                            player.statistics.minutes_played.into(),
+                            player.goals_scored.into(),
+                            player.assists.into(),
+                        ])
+                        .collect(),
+                );
+            }
+
+            output
+        },
+        ApiCommand::Fetch => {
+            let mut player_db = PlayerDB::with_ingest_policy(args.players);
+
+            for (_, competition_db) in load_player_dbs(client, args).await? {
+                player_db.extend(competition_db);
+            }
+
+            let mut output = Output::new(&["players", "cache_location"]);
+
+            output.push_text(format!(
+                "Fetched statistics for {} players into {}",
+                player_db.players.len(),
+                args.cache_location
+            ));
+            output.push_row(vec![
+                player_db.players.len().into(),
+                args.cache_location.clone().into(),
+            ]);
+
+            output
+        },
+        ApiCommand::Seasons => {
+            let competition = args.competition();
+            let competition_seasons = fetch_competition_seasons(client, competition).await?;
+
+            // mark the season the other commands would use
+            let today = Local::now().date_naive();
+            let selected: Vec<String> = competition
+                .season(&args.season)
+                .select_all(&competition_seasons.seasons, today)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|s| s.id.clone())
+                .collect();
+
+            let mut output = Output::new(&[
+                "selected",
+                "season_id",
+                "name",
+                "year",
+                "start_date",
+                "end_date",
+            ]);
+
+            for season in competition_seasons.seasons.iter() {
+                let is_selected = selected.contains(&season.id);
+                let marker = if is_selected { "*" } else { " " };
+
+                output.push_text(format!(
+                    "{} {} | {} | {} | {} - {}",
+                    marker, season.id, season.name, season.year, season.start_date, season.end_date
+                ));
+                output.push_row(vec![
+                    is_selected.into(),
+                    season.id.clone().into(),
+                    season.name.clone().into(),
+                    season.year.clone().into(),
+                    season.start_date.to_string().into(),
+                    season.end_date.to_string().into(),
+                ]);
+            }
+
+            output
+        },
+        ApiCommand::Competitors => {
+            let season = load_season(client, args, args.competition()).await?;
+            let competitors = client
+                .season_competitors(&season.id)
+                .await
+                .context("Failed to fetch season competitors")?;
+
+            let mut output = Output::new(&["team_id", "team"]);
+
+            for competitor in competitors.season_competitors.iter() {
+                output.push_text(format!("{} | {}", competitor.id, competitor.name));
+                output.push_row(vec![
+                    competitor.id.clone().into(),
+                    competitor.name.clone().into(),
+                ]);
+            }
+
+            output
+        },
+        ApiCommand::Fixtures { team, from, to } => {
+            let season = load_season(client, args, args.competition()).await?;
+            let schedules = client
+                .season_schedules(&season.id)
+                .await
+                .context("Failed to fetch season schedules")?;
+
+            let filter = FixtureFilter { team, from, to };
+            let fixtures = season_fixtures(&schedules, &filter);
+
+            let mut output = Output::new(&[
+                "match_id",
+                "start_time",
+                "status",
+                "home_id",
+                "home",
+                "home_score",
+                "away_score",
+                "away_id",
+                "away",
+                "venue",
+            ]);
+
+            for state in FixtureState::ALL {
+                let fixtures: Vec<_> = fixtures.iter().filter(|f| f.state() == state).collect();
+
+                // live and postponed matches are rare, only list them when there are some
+                if fixtures.is_empty()
+                    && matches!(state, FixtureState::Live | FixtureState::Postponed)
+                {
                     continue;
                 }
 
-                // loop through data to create Player data
-                for player in data.competitor.players.iter() {
-                    // skip players with 0 goals
-                    if player.statistics.goals_scored == 0 {
-                        continue;
-                    }
-
-                    let player: Player = Player {
-                        id: player.id.clone(),
-                        name: player.name.clone(),
-                        goals_scored: player.statistics.goals_scored,
-                        assists: player.statistics.assists,
-                    };
-
-                    // add player to player database
-                    player_db.add_player(player);
+                output.push_text(format!("{}:", state.title()));
+
+                if fixtures.is_empty() {
+                    output.push_text("No matches found");
+                }
+
+                for fixture in fixtures {
+                    output.push_text(fixture.to_string());
+                    output.push_row(vec![
+                        fixture.id.clone().into(),
+                        fixture.start_time.to_rfc3339().into(),
+                        fixture.status.clone().into(),
+                        fixture.home.id.clone().into(),
+                        fixture.home.name.clone().into(),
+                        fixture.home.score.into(),
+                        fixture.away.score.into(),
+                        fixture.away.id.clone().into(),
+                        fixture.away.name.clone().into(),
+                        fixture.venue.clone().into(),
+                    ]);
+                }
+
+                output.push_text("");
+            }
+
+            output
+        },
+        ApiCommand::Standings { standing_type } => {
+            let season = load_season(client, args, args.competition()).await?;
+            let standings = client
+                .season_standings(&season.id)
+                .await
+                .context("Failed to fetch season standings")?;
+
+            let groups = standing_groups(&standings, standing_type);
+            let mut output = Output::new(&[
+                "group",
+                "rank",
+                "team_id",
+                "team",
+                "played",
+                "win",
+                "draw",
+                "loss",
+                "goals_for",
+                "goals_against",
+                "goals_diff",
+                "points",
+                "form",
+            ]);
+
+            if groups.is_empty() {
+                output.push_text(format!("No {} standings found", standing_type.as_str()));
+            }
+
+            for group in groups.iter() {
+                let name = group.name.as_deref().unwrap_or(&season.name);
+
+                output.push_text(format!("{}:", name));
+
+                for line in standings_table(group) {
+                    output.push_text(line);
+                }
+
+                output.push_text("");
+
+                for row in group.standings.iter() {
+                    output.push_row(vec![
+                        name.into(),
+                        row.rank.into(),
+                        row.competitor.id.clone().into(),
+                        row.competitor.name.clone().into(),
+                        row.played.into(),
+                        row.win.into(),
+                        row.draw.into(),
+                        row.loss.into(),
+                        row.goals_for.into(),
+                        row.goals_against.into(),
+                        row.goals_diff.into(),
+                        row.points.into(),
+                        row.form.clone().into(),
+                    ]);
+                }
+            }
+
+            output
+        },
+    };
+
+    Ok(output)
+}
+
+/// Build the [`PlayerDB`]s and write a leaderboard for each of the stats
+async fn leaderboards_output(
+    client: &SportradarClient,
+    args: &ApiArgs,
+    leaderboard_args: &LeaderboardArgs,
+    stats: &[StatKey],
+) -> Result<Output> {
+    let mut output = Output::new(LEADERBOARD_COLUMNS);
+
+    for (heading, player_db) in leaderboard_dbs(client, args, leaderboard_args).await? {
+        push_heading(&mut output, heading);
+
+        for stat in stats {
+            let leaderboard =
+                player_db.leaderboard(*stat, leaderboard_args.limit, leaderboard_args.ties);
+
+            player_db.write_leaderboard(&leaderboard, &mut output);
+        }
+    }
+
+    Ok(output)
+}
+
+/// Build the [`PlayerDB`]s the leaderboards rank players from. A single one
+/// combining every competition, or one for each competition headed by its
+/// name with `--per-competition`
+async fn leaderboard_dbs(
+    client: &SportradarClient,
+    args: &ApiArgs,
+    leaderboard_args: &LeaderboardArgs,
+) -> Result<Vec<(Option<String>, PlayerDB)>> {
+    let player_dbs = load_player_dbs(client, args).await?;
+
+    if leaderboard_args.per_competition {
+        return Ok(player_dbs
+            .into_iter()
+            .map(|(season, player_db)| (Some(season.name), player_db))
+            .collect());
+    }
+
+    let mut combined = PlayerDB::with_ingest_policy(args.players);
+
+    for (_, player_db) in player_dbs {
+        combined.extend(player_db);
+    }
+
+    Ok(vec![(None, combined)])
+}
+
+/// Add the heading of a competition's leaderboards to the text output
+fn push_heading(output: &mut Output, heading: Option<String>) {
+    if let Some(heading) = heading {
+        output.push_text("");
+        output.push_text(format!("{}:", heading));
+    }
+}
+
+/// Fetch the seasons of a competition and pick the one we are checking
+async fn load_season(
+    client: &SportradarClient,
+    args: &ApiArgs,
+    competition: &CompetitionSelector,
+) -> Result<ApiCompetitionSeason> {
+    let competition_seasons = fetch_competition_seasons(client, competition).await?;
+    let selector = competition.season(&args.season);
+
+    // several seasons can't be listed as one
+    if matches!(selector, SeasonSelector::Last(n) if *n > 1) {
+        bail!(
+            "`{}` selects several seasons, which only the leaderboard and history commands \
+             use, select a single season with --season",
+            selector
+        );
+    }
+
+    // extract the season matching the selector
+    let today = Local::now().date_naive();
+    let season = selector.select(&competition_seasons.seasons, today)?;
+
+    Ok(season.clone())
+}
+
+/// Fetch the seasons of a competition
+async fn fetch_competition_seasons(
+    client: &SportradarClient,
+    competition: &CompetitionSelector,
+) -> Result<ApiCompetitionSeasons> {
+    client
+        .competition_seasons(&competition.competition_id)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to fetch the seasons of {}",
+                competition.competition_id
+            )
+        })
+}
+
+/// Fetch the seasons of a competition and pick every one we are checking,
+/// oldest first
+async fn load_seasons(
+    client: &SportradarClient,
+    args: &ApiArgs,
+    competition: &CompetitionSelector,
+) -> Result<Vec<ApiCompetitionSeason>> {
+    let competition_seasons = fetch_competition_seasons(client, competition).await?;
+
+    let today = Local::now().date_naive();
+    let seasons = competition
+        .season(&args.season)
+        .select_all(&competition_seasons.seasons, today)?;
+
+    Ok(seasons.into_iter().cloned().collect())
+}
+
+/// Build a [`PlayerDB`] for each selected season of each competition
+async fn load_player_dbs(
+    client: &SportradarClient,
+    args: &ApiArgs,
+) -> Result<Vec<(ApiCompetitionSeason, PlayerDB)>> {
+    let mut player_dbs = Vec::new();
+
+    for competition in args.competitions.iter() {
+        for season in load_seasons(client, args, competition).await? {
+            let player_db = load_player_db(client, args, &season).await?;
+
+            player_dbs.push((season, player_db));
+        }
+    }
+
+    Ok(player_dbs)
+}
+
+/// Fetch the statistics of every competitor in a season and build the
+/// [`PlayerDB`] from them, warning about competitors that failed
+async fn load_player_db(
+    client: &SportradarClient,
+    args: &ApiArgs,
+    season: &ApiCompetitionSeason,
+) -> Result<PlayerDB> {
+    let season_players = client
+        .season_players(&season.id)
+        .await
+        .with_context(|| format!("Failed to fetch statistics for {}", season.name))?;
+
+    warn_failed_competitors("statistics", &season_players.failed_competitors);
+
+    let mut player_db = season_players.player_db;
+
+    if args.profiles {
+        let failed_competitors = client.add_competitor_profiles(&mut player_db).await;
+
+        warn_failed_competitors("profiles", &failed_competitors);
+    }
+
+    player_db.set_season(season);
+
+    Ok(player_db)
+}
+
+/// Format the stats of a player's season history
+fn history_stats(goals: u32, assists: u32, statistics: &PlayerStatistics) -> String {
+    let mut stats = format!("{} goals, {} assists", goals, assists);
+
+    if let Some(matches_played) = statistics.matches_played {
+        stats.push_str(&format!(", {} matches", matches_played));
+    }
+
+    if let Some(minutes_played) = statistics.minutes_played {
+        stats.push_str(&format!(", {} minutes", minutes_played));
+    }
+
+    stats
+}
+
+/// Warn about the competitors whose `data` could not be fetched
+fn warn_failed_competitors(data: &str, failed_competitors: &[FailedCompetitor]) {
+    if failed_competitors.is_empty() {
+        return;
+    }
+
+    warn!(
+        "Failed to fetch {} for {} competitors, results are incomplete:",
+        data,
+        failed_competitors.len()
+    );
+
+    for competitor in failed_competitors.iter() {
+        warn!(
+            " - {} ({}): {}",
+            competitor.name, competitor.id, competitor.error
+        );
+    }
+}
+
+/// Run one of the `cache` subcommands against the cache directory
+fn run_cache_command(command: &CacheCommand, cache_location: &str) -> Result<Output> {
+    let cache_dir = Path::new(cache_location);
+    let mut output = match command {
+        CacheCommand::List => Output::new(&["path", "bytes", "fetched_at", "url"]),
+        CacheCommand::Clear => Output::new(&["removed"]),
+    };
+
+    if !cache_dir.exists() {
+        output.push_text(format!("No cache found at {}", cache_location));
+
+        return Ok(output);
+    }
+
+    match command {
+        CacheCommand::List => {
+            for path in list_cache_files(cache_dir)? {
+                let metadata = fs::metadata(&path).context("Failed to read cache file metadata")?;
+
+                let cache_entry = read_cache_entry_info(&path);
+
+                match &cache_entry {
+                    Some(cache_entry) => output.push_text(format!(
+                        "{} ({} bytes) | fetched {} ago | {}",
+                        path.display(),
+                        metadata.len(),
+                        humantime::format_duration(Duration::from_secs(
+                            cache_entry.age().as_secs()
+                        )),
+                        cache_entry.url
+                    )),
+                    None => output.push_text(format!(
+                        "{} ({} bytes) | not a cache entry",
+                        path.display(),
+                        metadata.len()
+                    )),
                 }
 
-                // index players to keep track of top scorers and assists
-                player_db.index_players();
+                output.push_row(vec![
+                    path.display().to_string().into(),
+                    metadata.len().into(),
+                    cache_entry
+                        .as_ref()
+                        .map(|entry| entry.fetched_at.to_rfc3339())
+                        .into(),
+                    cache_entry.map(|entry| entry.url).into(),
+                ]);
+            }
+        },
+        CacheCommand::Clear => {
+            let removed = clear_cache(cache_dir).context("Failed to clear the cache")?;
+
+            output.push_text(format!(
+                "Removed {} cache files from {}",
+                removed.len(),
+                cache_location
+            ));
 
-                Ok(())
-            },
-            Err(err) => Err(err),
-        }?;
+            for path in removed {
+                output.push_row(vec![path.display().to_string().into()]);
+            }
+        },
     }
 
-    // Print top scorers and assists
-    player_db.print_top_scorers();
-    player_db.print_top_assists();
+    Ok(output)
+}
 
-    Ok(())
+/// Run one of the `config` subcommands
+fn run_config_command(command: &ConfigCommand, cli: &Cli) -> Output {
+    match command {
+        ConfigCommand::Show => {
+            let mut output = Output::new(&["setting", "value", "source"]);
+
+            if let Some(profile) = &cli.profile {
+                output.push_text(format!("# profile: {}", profile));
+            }
+
+            // printed as TOML, so it can be copied into a config file
+            for key in SETTINGS {
+                let value = cli.get(key);
+                let source = cli.sources.get(key).cloned().unwrap_or_default();
+
+                match &value {
+                    Value::Null => output.push_text(format!("# {} is not set", key)),
+                    // never printed as an assignment, copying it would overwrite the key
+                    _ if *key == "api_key" => {
+                        output.push_text(format!("# {} is set (redacted), from {}", key, source))
+                    },
+                    value => output.push_text(format!("{} = {} # {}", key, value, source)),
+                }
+
+                output.push_row(vec![(*key).into(), value, source.to_string().into()]);
+            }
+
+            output
+        },
+    }
 }
@@ -0,0 +1,263 @@
+use clap::ValueEnum;
+use serde_json::Value;
+
+/// Formats the output of a command can be rendered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// Columns aligned in a table
+    Table,
+    /// A JSON array with an object per row
+    Json,
+    /// A JSON object per line
+    Ndjson,
+    /// Comma separated values, with a header
+    Csv,
+    /// A Markdown table
+    Markdown,
+}
+
+/// Output of a command, as rows with stable column names for scripts, and as
+/// text for humans
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Output {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+    text: Vec<String>,
+}
+
+impl Output {
+    /// Create a new [`Output`] with rows of the given columns
+    pub fn new(columns: &[&'static str]) -> Self {
+        Self {
+            columns: columns.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Add a row, with a value for each column
+    pub fn push_row(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.columns.len(), "row doesn't match columns");
+
+        self.rows.push(row);
+    }
+
+    /// Add a line to the text output
+    pub fn push_text(&mut self, line: impl Into<String>) {
+        self.text.push(line.into());
+    }
+
+    /// Rows of the output
+    pub fn rows(&self) -> &[Vec<Value>] {
+        &self.rows
+    }
+
+    /// Render the output in a format
+    pub fn render(&self, format: OutputFormat) -> String {
+        let lines = match format {
+            OutputFormat::Text => self.text.clone(),
+            OutputFormat::Table => self.table(),
+            OutputFormat::Json => {
+                let objects: Vec<String> = self.rows.iter().map(|row| self.object(row)).collect();
+
+                vec![format!("[{}]", objects.join(","))]
+            },
+            OutputFormat::Ndjson => self.rows.iter().map(|row| self.object(row)).collect(),
+            OutputFormat::Csv => {
+                let header = self.columns.iter().map(|column| csv_field(column));
+                let rows = self.rows.iter().map(|row| {
+                    row.iter()
+                        .map(|value| csv_field(&cell(value)))
+                        .collect::<Vec<_>>()
+                        .join(",")
+                });
+
+                std::iter::once(header.collect::<Vec<_>>().join(","))
+                    .chain(rows)
+                    .collect()
+            },
+            OutputFormat::Markdown => {
+                let row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+
+                let mut lines = vec![
+                    row(self.columns.iter().map(|c| c.to_string()).collect()),
+                    row(self.columns.iter().map(|_| "---".to_string()).collect()),
+                ];
+
+                lines.extend(self.rows.iter().map(|values| {
+                    row(values
+                        .iter()
+                        .map(|value| cell(value).replace('|', "\\|"))
+                        .collect())
+                }));
+
+                lines
+            },
+        };
+
+        lines.into_iter().map(|line| line + "\n").collect()
+    }
+
+    /// Format a row as a JSON object, keeping the order of the columns
+    fn object(&self, row: &[Value]) -> String {
+        let fields: Vec<String> = self
+            .columns
+            .iter()
+            .zip(row)
+            .map(|(column, value)| format!("{}:{}", Value::from(*column), value))
+            .collect();
+
+        format!("{{{}}}", fields.join(","))
+    }
+
+    /// Format the rows as a table, aligning the columns and numbers to the
+    /// right
+    fn table(&self) -> Vec<String> {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(cell).collect())
+            .collect();
+
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                cells
+                    .iter()
+                    .map(|row| row[i].chars().count())
+                    .chain([column.len()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let line = |values: Vec<(String, bool)>| {
+            values
+                .into_iter()
+                .zip(&widths)
+                .map(|((value, number), &width)| match number {
+                    true => format!("{:>width$}", value),
+                    false => format!("{:<width$}", value),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = vec![
+            line(
+                self.columns
+                    .iter()
+                    .map(|c| (c.to_string(), false))
+                    .collect(),
+            ),
+            line(widths.iter().map(|&w| ("-".repeat(w), false)).collect()),
+        ];
+
+        lines.extend(self.rows.iter().zip(cells).map(|(row, cells)| {
+            line(
+                row.iter()
+                    .zip(cells)
+                    .map(|(value, cell)| (cell, value.is_number()))
+                    .collect(),
+            )
+        }));
+
+        lines
+    }
+}
+
+/// Format a value as a plain cell, without the quotes of strings
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Quote a CSV field when it contains separators, quotes or new lines
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn output() -> Output {
+        let mut output = Output::new(&["rank", "player", "team", "value"]);
+
+        output.push_row(vec![
+            json!(1),
+            json!("Haaland, Erling"),
+            json!("Manchester City"),
+            json!(27),
+        ]);
+        output.push_row(vec![
+            json!(2),
+            json!("Palmer | Cole"),
+            Value::Null,
+            json!(22),
+        ]);
+        output.push_text("1: Haaland, Erling (Manchester City) 27 goals");
+
+        output
+    }
+
+    // test rows are rendered in every format with the same fields
+    #[test]
+    fn test_output_render() {
+        let output = output();
+
+        assert_eq!(
+            output.render(OutputFormat::Text),
+            "1: Haaland, Erling (Manchester City) 27 goals\n"
+        );
+        assert_eq!(
+            output.render(OutputFormat::Json),
+            concat!(
+                r#"[{"rank":1,"player":"Haaland, Erling","team":"Manchester City","value":27},"#,
+                r#"{"rank":2,"player":"Palmer | Cole","team":null,"value":22}]"#,
+                "\n"
+            )
+        );
+        assert_eq!(
+            output.render(OutputFormat::Ndjson).lines().nth(1),
+            Some(r#"{"rank":2,"player":"Palmer | Cole","team":null,"value":22}"#)
+        );
+        assert_eq!(
+            output.render(OutputFormat::Csv),
+            "rank,player,team,value\n1,\"Haaland, Erling\",Manchester City,27\n2,Palmer | Cole,,22\n"
+        );
+        assert_eq!(
+            output.render(OutputFormat::Markdown),
+            concat!(
+                "| rank | player | team | value |\n",
+                "| --- | --- | --- | --- |\n",
+                "| 1 | Haaland, Erling | Manchester City | 27 |\n",
+                "| 2 | Palmer \\| Cole |  | 22 |\n"
+            )
+        );
+        assert_eq!(
+            output.render(OutputFormat::Table),
+            concat!(
+                "rank  player           team             value\n",
+                "----  ---------------  ---------------  -----\n",
+                "   1  Haaland, Erling  Manchester City     27\n",
+                "   2  Palmer | Cole                        22\n"
+            )
+        );
+    }
+}
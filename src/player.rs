@@ -1,15 +1,278 @@
 use std::{
     cmp::Ordering,
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    error::Error,
+    fmt,
+    ops::Add,
+};
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde_json::Value;
+
+use crate::{
+    api::{
+        ApiCompetitionSeason,
+        ApiCompetitorProfile,
+        ApiPlayerGameStatistic,
+        ApiSeasonCompetitorStatistics,
+    },
+    output::Output,
 };
 
 pub type PlayerId = String;
 
+/// Columns of the rows written for each player of a leaderboard
+pub const LEADERBOARD_COLUMNS: &[&str] = &[
+    "stat",
+    "rank",
+    "player_id",
+    "player",
+    "team",
+    "competition",
+    "position",
+    "nationality",
+    "date_of_birth",
+    "value",
+];
+
+/// Default number of players kept in a leaderboard
+pub const LEADERBOARD_LIMIT: usize = 10;
+
+/// Default minutes a player must have played to be ranked by a [`Metric`],
+/// five full matches
+pub const MIN_MINUTES: u16 = 450;
+
 /// PlayerDB is a database of players with their stats
 pub struct PlayerDB {
     pub players: HashMap<PlayerId, Player>,
-    pub top_scorers: Vec<PlayerId>,
-    pub top_assists: Vec<PlayerId>,
+    /// Which players of the competitor statistics are added
+    pub ingest_policy: IngestPolicy,
+}
+
+/// Which players of the competitor statistics are added to the [`PlayerDB`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum IngestPolicy {
+    /// Players with any nonzero stat, e.g. an assist without a goal
+    #[default]
+    Active,
+    /// Every player of the squad, even those who never played
+    All,
+}
+
+/// Player stats that leaderboards can be ranked by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum StatKey {
+    /// Goals scored
+    Goals,
+    /// Assists provided
+    Assists,
+    /// Goals scored plus assists provided
+    GoalContributions,
+    /// Matches played
+    MatchesPlayed,
+    /// Minutes played
+    Minutes,
+    /// Shots on target, off target and blocked
+    Shots,
+    /// Shots on target
+    ShotsOnTarget,
+    /// Yellow cards received
+    YellowCards,
+    /// Red cards received, including second yellow cards
+    RedCards,
+}
+
+impl StatKey {
+    /// Get the value of the stat for a [`Player`], stats the API didn't
+    /// provide count as zero
+    pub fn value(&self, player: &Player) -> u32 {
+        let stats = &player.statistics;
+        let sum = |values: &[Option<u32>]| values.iter().flatten().sum();
+
+        match self {
+            StatKey::Goals => player.goals_scored,
+            StatKey::Assists => player.assists,
+            StatKey::GoalContributions => player.goals_scored + player.assists,
+            StatKey::MatchesPlayed => sum(&[stats.matches_played]),
+            StatKey::Minutes => sum(&[stats.minutes_played]),
+            StatKey::Shots => sum(&[
+                stats.shots_on_target,
+                stats.shots_off_target,
+                stats.shots_blocked,
+            ]),
+            StatKey::ShotsOnTarget => sum(&[stats.shots_on_target]),
+            StatKey::YellowCards => sum(&[stats.yellow_cards]),
+            StatKey::RedCards => sum(&[stats.red_cards, stats.yellow_red_cards]),
+        }
+    }
+
+    /// Title of the leaderboard ranked by the stat
+    pub fn title(&self) -> &'static str {
+        match self {
+            StatKey::Goals => "Top Scorers",
+            StatKey::Assists => "Top Assists",
+            StatKey::GoalContributions => "Top Goal Contributions",
+            StatKey::MatchesPlayed => "Most Matches Played",
+            StatKey::Minutes => "Most Minutes Played",
+            StatKey::Shots => "Most Shots",
+            StatKey::ShotsOnTarget => "Most Shots on Target",
+            StatKey::YellowCards => "Most Yellow Cards",
+            StatKey::RedCards => "Most Red Cards",
+        }
+    }
+
+    /// Unit printed next to the value of the stat
+    pub fn unit(&self) -> &'static str {
+        match self {
+            StatKey::Goals => "goals",
+            StatKey::Assists => "assists",
+            StatKey::GoalContributions => "goal contributions",
+            StatKey::MatchesPlayed => "matches",
+            StatKey::Minutes => "minutes",
+            StatKey::Shots => "shots",
+            StatKey::ShotsOnTarget => "shots on target",
+            StatKey::YellowCards => "yellow cards",
+            StatKey::RedCards => "red cards",
+        }
+    }
+}
+
+/// Efficiency metrics derived from a [`Player`]'s stats, that leaderboards can
+/// be ranked by. They reward output over volume, so they are only computed
+/// for players who played a minimum number of minutes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub enum Metric {
+    /// Goals scored per 90 minutes
+    #[value(name = "goals-per-90")]
+    GoalsPer90,
+    /// Assists provided per 90 minutes
+    #[value(name = "assists-per-90")]
+    AssistsPer90,
+    /// Goals scored plus assists provided per 90 minutes
+    #[value(name = "goal-contributions-per-90")]
+    GoalContributionsPer90,
+    /// Percentage of shots that were goals
+    ShotConversion,
+    /// Minutes played per goal scored, lower is better
+    MinutesPerGoal,
+}
+
+impl Metric {
+    /// Get the value of the metric for a [`Player`], if the player played at
+    /// least `min_minutes` and the stats it is derived from are available
+    pub fn value(&self, player: &Player, min_minutes: u16) -> Option<f64> {
+        let minutes = player
+            .statistics
+            .minutes_played
+            .filter(|&minutes| minutes > 0 && minutes >= min_minutes.into())?
+            as f64;
+        let per_90 = |stat: StatKey| stat.value(player) as f64 * 90.0 / minutes;
+
+        match self {
+            Metric::GoalsPer90 => Some(per_90(StatKey::Goals)),
+            Metric::AssistsPer90 => Some(per_90(StatKey::Assists)),
+            Metric::GoalContributionsPer90 => Some(per_90(StatKey::GoalContributions)),
+            Metric::ShotConversion => {
+                let shots = StatKey::Shots.value(player);
+
+                (shots > 0).then(|| player.goals_scored as f64 * 100.0 / shots as f64)
+            },
+            Metric::MinutesPerGoal => {
+                (player.goals_scored > 0).then(|| minutes / player.goals_scored as f64)
+            },
+        }
+    }
+
+    /// Check if higher values of the metric rank first
+    pub fn higher_is_better(&self) -> bool {
+        *self != Metric::MinutesPerGoal
+    }
+}
+
+impl LeaderboardStat for Metric {
+    type Value = f64;
+
+    fn title(&self) -> &'static str {
+        match self {
+            Metric::GoalsPer90 => "Top Goals per 90",
+            Metric::AssistsPer90 => "Top Assists per 90",
+            Metric::GoalContributionsPer90 => "Top Goal Contributions per 90",
+            Metric::ShotConversion => "Top Shot Conversion",
+            Metric::MinutesPerGoal => "Fewest Minutes per Goal",
+        }
+    }
+
+    fn format_value(&self, value: f64) -> String {
+        match self {
+            Metric::GoalsPer90 => format!("{:.2} goals per 90", value),
+            Metric::AssistsPer90 => format!("{:.2} assists per 90", value),
+            Metric::GoalContributionsPer90 => format!("{:.2} goal contributions per 90", value),
+            Metric::ShotConversion => format!("{:.1}% of shots scored", value),
+            Metric::MinutesPerGoal => format!("{:.0} minutes per goal", value),
+        }
+    }
+}
+
+/// What a [`Leaderboard`] ranks players by
+pub trait LeaderboardStat: Copy + ValueEnum {
+    /// Type of the values players are ranked by
+    type Value: Copy + PartialEq + fmt::Debug + Into<Value>;
+
+    /// Name of the stat on the command line and in structured output
+    fn name(&self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Title of the leaderboard
+    fn title(&self) -> &'static str;
+
+    /// Format a value of the stat to be printed next to the player
+    fn format_value(&self, value: Self::Value) -> String;
+}
+
+impl LeaderboardStat for StatKey {
+    type Value = u32;
+
+    fn title(&self) -> &'static str {
+        StatKey::title(self)
+    }
+
+    fn format_value(&self, value: u32) -> String {
+        format!("{} {}", value, self.unit())
+    }
+}
+
+/// How a leaderboard is cut and ranked when players are tied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum TiePolicy {
+    /// Keep exactly `limit` players, tied players share a dense rank (1, 2, 2,
+    /// 3)
+    Strict,
+    /// Keep everyone tied with the last place, tied players share a dense rank
+    /// (1, 2, 2, 3)
+    #[default]
+    Include,
+    /// Keep everyone tied with the last place, using standard competition
+    /// ranking (1, 2, 2, 4)
+    Competition,
+}
+
+/// Players ranked by a stat or a metric, best first
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leaderboard<S: LeaderboardStat = StatKey> {
+    pub stat: S,
+    pub entries: Vec<RankedPlayer<S::Value>>,
+}
+
+/// A player's position in a leaderboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedPlayer<V = u32> {
+    pub rank: usize,
+    pub id: PlayerId,
+    pub value: V,
 }
 
 impl Default for PlayerDB {
@@ -21,159 +284,512 @@ impl Default for PlayerDB {
 impl PlayerDB {
     /// Create a new [`PlayerDB`]
     pub fn new() -> Self {
+        Self::with_ingest_policy(IngestPolicy::default())
+    }
+
+    /// Create a new [`PlayerDB`] adding the players allowed by the
+    /// [`IngestPolicy`]
+    pub fn with_ingest_policy(ingest_policy: IngestPolicy) -> Self {
         Self {
             players: HashMap::new(),
-            top_scorers: Vec::new(),
-            top_assists: Vec::new(),
+            ingest_policy,
         }
     }
 
-    /// Add [`Player`] to list
+    /// Add [`Player`] to list. A player already in the list, who
+    /// transferred between competitors during the season, gets the stats of
+    /// both competitors combined
     pub fn add_player(&mut self, player: Player) {
-        self.players.insert(player.id.clone(), player);
+        match self.players.get_mut(&player.id) {
+            Some(existing) if !player.stints.is_empty() => existing.merge(player),
+            _ => {
+                self.players.insert(player.id.clone(), player);
+            },
+        }
     }
 
-    /// Index top_scorers and top_assists
-    pub fn index_players(&mut self) {
-        // calculate top scorers and add ids to top_scorers, descending order by
-        // goals_scored limited to 10 players
-        let mut top_scorers: BTreeSet<PlayerWithScore> = BTreeSet::new();
-        for (id, player) in self.players.iter() {
-            top_scorers.insert(PlayerWithScore {
-                id: id.clone(),
-                score: player.goals_scored as u32,
-            });
+    /// Add the players of another [`PlayerDB`], e.g. of another competition,
+    /// combining the stats of players in both
+    pub fn extend(&mut self, other: PlayerDB) {
+        for player in other.players.into_values() {
+            self.add_player(player);
+        }
+    }
+
+    /// Record the competition season the stints of the players were played
+    /// in, so players of several competitions can be told apart
+    pub fn set_season(&mut self, season: &ApiCompetitionSeason) {
+        for stint in self.players.values_mut().flat_map(|p| p.stints.iter_mut()) {
+            stint.season_id = season.id.clone();
+            stint.season_name = season.name.clone();
         }
+    }
 
-        // take more than 10 if they are tied
-        self.top_scorers = top_scorers.into_iter().take(10).map(|p| p.id).collect();
+    /// Add the players of a competitor's statistics
+    pub fn add_competitor_statistics(&mut self, data: &ApiSeasonCompetitorStatistics) {
+        // loop through data to create Player data
+        for player in data.competitor.players.iter() {
+            let stint = PlayerStint {
+                season_id: String::new(),
+                season_name: String::new(),
+                competitor_id: data.competitor.id.clone(),
+                competitor_name: data.competitor.name.clone(),
+                goals_scored: player.statistics.goals_scored.into(),
+                assists: player.statistics.assists.into(),
+                statistics: PlayerStatistics::from(&player.statistics),
+            };
 
-        // calculate top assists and add ids to top_assists, descending order by
-        // assists limited to 10 players
-        let mut top_assists: BTreeSet<PlayerWithAssists> = BTreeSet::new();
-        for (id, player) in self.players.iter() {
-            top_assists.insert(PlayerWithAssists {
-                id: id.clone(),
-                assists: player.assists as u32,
-            });
+            let player: Player = Player {
+                id: player.id.clone(),
+                name: player.name.clone(),
+                goals_scored: stint.goals_scored,
+                assists: stint.assists,
+                statistics: stint.statistics,
+                stints: vec![stint],
+                ..Default::default()
+            };
+
+            // skip players without any stat, unless we want the whole squad
+            if self.ingest_policy == IngestPolicy::Active && !player.has_stats() {
+                continue;
+            }
+
+            // add player to player database
+            self.add_player(player);
         }
-        self.top_assists = top_assists.into_iter().take(10).map(|p| p.id).collect();
     }
 
-    /// Print top scorers
-    pub fn print_top_scorers(&self) {
-        if self.top_scorers.is_empty() {
-            println!("No top scorers found");
+    /// Add the position, nationality and date of birth from a competitor's
+    /// profile to its players already in the database
+    pub fn add_competitor_profile(&mut self, data: &ApiCompetitorProfile) {
+        for profile in data.players.iter() {
+            if let Some(player) = self.players.get_mut(&profile.id) {
+                player.position = profile.position.clone();
+                player.nationality = profile.nationality.clone();
+                player.date_of_birth = profile.date_of_birth;
+            }
+        }
+    }
 
-            return;
+    /// Ids and names of the competitors the players played for
+    pub fn competitors(&self) -> BTreeMap<&str, &str> {
+        self.players
+            .values()
+            .flat_map(|player| player.stints.iter())
+            .map(|stint| (stint.competitor_id.as_str(), stint.competitor_name.as_str()))
+            .collect()
+    }
+
+    /// Find the players matching a player id or part of their name, ignoring
+    /// case, sorted by name
+    pub fn find_players(&self, query: &str) -> Result<Vec<&Player>, PlayerNotFound> {
+        let query = query.trim();
+        let search = query.to_lowercase();
+
+        let mut players: Vec<&Player> = self
+            .players
+            .values()
+            .filter(|p| p.id.to_lowercase() == search || p.name.to_lowercase().contains(&search))
+            .collect();
+
+        if players.is_empty() {
+            return Err(PlayerNotFound {
+                query: query.to_string(),
+            });
         }
 
-        println!();
-        println!();
-        println!();
-        println!("Top Scorers:");
+        players.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.cmp(&b.id)));
+
+        Ok(players)
+    }
 
-        let mut position = 1;
-        let mut prev_score = 0;
-        for (index, id) in self.top_scorers.iter().enumerate() {
-            let player = self.players.get(id).unwrap();
+    /// Rank players by `stat`, keeping `limit` players and handling players
+    /// tied around the limit according to `ties`
+    pub fn leaderboard(&self, stat: StatKey, limit: usize, ties: TiePolicy) -> Leaderboard {
+        // sort players in descending order by the stat, leaving out players
+        // without it so ties on zero don't fill the leaderboard
+        let mut sorted: BTreeSet<PlayerWithStat> = BTreeSet::new();
+        for (id, player) in self.players.iter() {
+            let value = stat.value(player);
 
-            if index == 0 {
-                prev_score = player.goals_scored
+            if value > 0 {
+                sorted.insert(PlayerWithStat {
+                    id: id.clone(),
+                    value,
+                });
             }
+        }
 
-            if prev_score > player.goals_scored {
-                position += 1;
-                prev_score = player.goals_scored;
-            }
+        Leaderboard {
+            stat,
+            entries: rank_players(sorted.into_iter().map(|p| (p.id, p.value)), limit, ties),
+        }
+    }
+
+    /// Rank players by `metric`, leaving out players who played less than
+    /// `min_minutes` or lack the stats the metric is derived from
+    pub fn metric_leaderboard(
+        &self,
+        metric: Metric,
+        limit: usize,
+        ties: TiePolicy,
+        min_minutes: u16,
+    ) -> Leaderboard<Metric> {
+        let mut sorted: Vec<(PlayerId, f64)> = self
+            .players
+            .iter()
+            .filter_map(|(id, player)| Some((id.clone(), metric.value(player, min_minutes)?)))
+            .collect();
+
+        // sort players best first, then by id to keep ties stable
+        sorted.sort_by(|(a_id, a), (b_id, b)| {
+            let order = if metric.higher_is_better() {
+                b.total_cmp(a)
+            } else {
+                a.total_cmp(b)
+            };
+
+            order.then_with(|| a_id.cmp(b_id))
+        });
 
-            println!(
-                "{}: {} ({} goals)",
-                position, player.name, player.goals_scored
-            );
+        Leaderboard {
+            stat: metric,
+            entries: rank_players(sorted, limit, ties),
         }
     }
 
-    /// Print top assists
-    pub fn print_top_assists(&self) {
-        if self.top_assists.is_empty() {
-            println!("No top assists found");
+    /// Write a [`Leaderboard`] to the output, with a row of
+    /// [`LEADERBOARD_COLUMNS`] for each ranked player
+    pub fn write_leaderboard<S: LeaderboardStat>(
+        &self,
+        leaderboard: &Leaderboard<S>,
+        output: &mut Output,
+    ) {
+        let title = leaderboard.stat.title();
+
+        if leaderboard.entries.is_empty() {
+            output.push_text(format!("No {} found", title.to_lowercase()));
 
             return;
         }
 
-        println!();
-        println!();
-        println!();
-        println!("Top Assists:");
+        output.push_text("");
+        output.push_text("");
+        output.push_text("");
+        output.push_text(format!("{}:", title));
 
-        let mut position = 1;
-        let mut prev_assists = 0;
+        for ranked in leaderboard.entries.iter() {
+            let player = self.players.get(&ranked.id).unwrap();
 
-        for (index, id) in self.top_assists.iter().enumerate() {
-            let player = self.players.get(id).unwrap();
+            output.push_text(format!(
+                "{}: {} {}",
+                ranked.rank,
+                player,
+                leaderboard.stat.format_value(ranked.value)
+            ));
+            output.push_row(vec![
+                leaderboard.stat.name().into(),
+                ranked.rank.into(),
+                player.id.clone().into(),
+                player.name.clone().into(),
+                player.teams().join(", ").into(),
+                player.competitions().join(", ").into(),
+                player.position.clone().into(),
+                player.nationality.clone().into(),
+                player.date_of_birth.map(|date| date.to_string()).into(),
+                ranked.value.into(),
+            ]);
+        }
+    }
+}
 
-            if index == 0 {
-                prev_assists = player.assists;
-            }
+/// Assign ranks to players already sorted best first, cutting the list at
+/// `limit` according to the [`TiePolicy`]
+fn rank_players<V: PartialEq>(
+    sorted: impl IntoIterator<Item = (PlayerId, V)>,
+    limit: usize,
+    ties: TiePolicy,
+) -> Vec<RankedPlayer<V>> {
+    let mut ranked: Vec<RankedPlayer<V>> = Vec::new();
+    let mut rank = 0;
 
-            if prev_assists > player.assists {
-                position += 1;
-                prev_assists = player.assists;
-            }
+    for (index, (id, value)) in sorted.into_iter().enumerate() {
+        let tied = ranked.last().is_some_and(|prev| prev.value == value);
 
-            println!("{}: {} ({} assists)", position, player.name, player.assists);
+        // stop at the limit, unless the policy keeps players tied with the
+        // last place
+        if index >= limit && (ties == TiePolicy::Strict || !tied) {
+            break;
         }
+
+        if !tied {
+            rank = match ties {
+                TiePolicy::Strict | TiePolicy::Include => rank + 1,
+                TiePolicy::Competition => index + 1,
+            };
+        }
+
+        ranked.push(RankedPlayer { rank, id, value });
     }
+
+    ranked
 }
 
 /// Player struct
+#[derive(Debug, Clone, Default)]
 pub struct Player {
     pub id: String,
     pub name: String,
-    pub goals_scored: u16,
-    pub assists: u16,
+    /// `goalkeeper`, `defender`, `midfielder` or `forward`, from the
+    /// competitor's profile
+    pub position: Option<String>,
+    /// From the competitor's profile
+    pub nationality: Option<String>,
+    /// From the competitor's profile
+    pub date_of_birth: Option<NaiveDate>,
+    /// Totals over every stint
+    pub goals_scored: u32,
+    pub assists: u32,
+    pub statistics: PlayerStatistics,
+    /// Stats for each competitor the player played for in each competition
+    /// season, more than one when the player transferred mid-season
+    pub stints: Vec<PlayerStint>,
 }
 
-/// Temporary struct to hold player id and score for sorting purposes in
-/// BTreeSet
-#[derive(Debug, Eq, PartialEq)]
-struct PlayerWithScore {
-    id: String,
-    score: u32,
+/// The stats of a [`Player`] for one of the competitors the player played
+/// for in a competition season
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlayerStint {
+    /// Competition season of the stint, set by [`PlayerDB::set_season`]
+    pub season_id: String,
+    pub season_name: String,
+    pub competitor_id: String,
+    pub competitor_name: String,
+    pub goals_scored: u32,
+    pub assists: u32,
+    pub statistics: PlayerStatistics,
 }
 
-impl Ord for PlayerWithScore {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other
-            .score
-            .cmp(&self.score)
-            .then_with(|| self.id.cmp(&other.id))
+impl Player {
+    /// Check if any stat of the player is nonzero
+    pub fn has_stats(&self) -> bool {
+        self.goals_scored > 0 || self.assists > 0 || self.statistics.has_stats()
+    }
+
+    /// Names of the competitors the player played for
+    pub fn teams(&self) -> Vec<&str> {
+        unique_names(self.stints.iter().map(|stint| &stint.competitor_name))
+    }
+
+    /// Names of the competition seasons the player played in
+    pub fn competitions(&self) -> Vec<&str> {
+        unique_names(self.stints.iter().map(|stint| &stint.season_name))
+    }
+
+    /// Position, nationality and date of birth of the player, the ones known
+    /// from the competitor's profile
+    pub fn details(&self) -> Vec<String> {
+        self.position
+            .iter()
+            .chain(self.nationality.iter())
+            .cloned()
+            .chain(self.date_of_birth.map(|date| format!("born {}", date)))
+            .collect()
+    }
+
+    /// Merge the stints of the same player at other competitors or in other
+    /// competitions, replacing stints added again, and total the stats of
+    /// every stint
+    fn merge(&mut self, other: Player) {
+        for stint in other.stints {
+            match self
+                .stints
+                .iter_mut()
+                .find(|s| s.season_id == stint.season_id && s.competitor_id == stint.competitor_id)
+            {
+                Some(existing) => *existing = stint,
+                None => self.stints.push(stint),
+            }
+        }
+
+        self.goals_scored = self.stints.iter().map(|s| s.goals_scored).sum();
+        self.assists = self.stints.iter().map(|s| s.assists).sum();
+        self.statistics = self
+            .stints
+            .iter()
+            .fold(PlayerStatistics::default(), |total, s| total + s.statistics);
+
+        self.position = self.position.take().or(other.position);
+        self.nationality = self.nationality.take().or(other.nationality);
+        self.date_of_birth = self.date_of_birth.or(other.date_of_birth);
     }
 }
 
-impl PartialOrd for PlayerWithScore {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Error returned when no player matches a search
+#[derive(Debug)]
+pub struct PlayerNotFound {
+    pub query: String,
+}
+
+impl fmt::Display for PlayerNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No player matching \"{}\" found", self.query)
+    }
+}
+
+impl Error for PlayerNotFound {}
+
+/// Remove the empty and repeated names, keeping the order
+fn unique_names<'a>(names: impl Iterator<Item = &'a String>) -> Vec<&'a str> {
+    let mut unique: Vec<&str> = Vec::new();
+
+    for name in names {
+        if !name.is_empty() && !unique.contains(&name.as_str()) {
+            unique.push(name);
+        }
+    }
+
+    unique
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let teams = self.teams();
+        let details = self.details();
+
+        write!(f, "{}", self.name)?;
+
+        if !teams.is_empty() {
+            write!(f, " ({})", teams.join(", "))?;
+        }
+
+        if !details.is_empty() {
+            write!(f, " [{}]", details.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Statistics of a [`Player`] besides goals and assists. They are optional,
+/// as the API leaves out the ones not available for the account's access
+/// level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlayerStatistics {
+    pub matches_played: Option<u32>,
+    pub minutes_played: Option<u32>,
+    pub shots_on_target: Option<u32>,
+    pub shots_off_target: Option<u32>,
+    pub shots_blocked: Option<u32>,
+    pub yellow_cards: Option<u32>,
+    pub yellow_red_cards: Option<u32>,
+    pub red_cards: Option<u32>,
+    pub substituted_in: Option<u32>,
+    pub substituted_out: Option<u32>,
+    pub goals_by_penalty: Option<u32>,
+    pub penalties_missed: Option<u32>,
+    pub own_goals: Option<u32>,
+    pub offsides: Option<u32>,
+    pub corner_kicks: Option<u32>,
+}
+
+impl PlayerStatistics {
+    /// Check if any of the statistics is nonzero
+    pub fn has_stats(&self) -> bool {
+        [
+            self.matches_played,
+            self.minutes_played,
+            self.shots_on_target,
+            self.shots_off_target,
+            self.shots_blocked,
+            self.yellow_cards,
+            self.yellow_red_cards,
+            self.red_cards,
+            self.substituted_in,
+            self.substituted_out,
+            self.goals_by_penalty,
+            self.penalties_missed,
+            self.own_goals,
+            self.offsides,
+            self.corner_kicks,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|value| value > 0)
+    }
+}
+
+impl Add for PlayerStatistics {
+    type Output = Self;
+
+    /// Add the statistics of two stints, statistics missing from both stay
+    /// missing
+    fn add(self, other: Self) -> Self {
+        let add = |a: Option<u32>, b: Option<u32>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.saturating_add(b)),
+            (a, b) => a.or(b),
+        };
+
+        Self {
+            matches_played: add(self.matches_played, other.matches_played),
+            minutes_played: add(self.minutes_played, other.minutes_played),
+            shots_on_target: add(self.shots_on_target, other.shots_on_target),
+            shots_off_target: add(self.shots_off_target, other.shots_off_target),
+            shots_blocked: add(self.shots_blocked, other.shots_blocked),
+            yellow_cards: add(self.yellow_cards, other.yellow_cards),
+            yellow_red_cards: add(self.yellow_red_cards, other.yellow_red_cards),
+            red_cards: add(self.red_cards, other.red_cards),
+            substituted_in: add(self.substituted_in, other.substituted_in),
+            substituted_out: add(self.substituted_out, other.substituted_out),
+            goals_by_penalty: add(self.goals_by_penalty, other.goals_by_penalty),
+            penalties_missed: add(self.penalties_missed, other.penalties_missed),
+            own_goals: add(self.own_goals, other.own_goals),
+            offsides: add(self.offsides, other.offsides),
+            corner_kicks: add(self.corner_kicks, other.corner_kicks),
+        }
+    }
+}
+
+impl From<&ApiPlayerGameStatistic> for PlayerStatistics {
+    fn from(statistics: &ApiPlayerGameStatistic) -> Self {
+        Self {
+            matches_played: statistics.matches_played.map(u32::from),
+            minutes_played: statistics.minutes_played.map(u32::from),
+            shots_on_target: statistics.shots_on_target.map(u32::from),
+            shots_off_target: statistics.shots_off_target.map(u32::from),
+            shots_blocked: statistics.shots_blocked.map(u32::from),
+            yellow_cards: statistics.yellow_cards.map(u32::from),
+            yellow_red_cards: statistics.yellow_red_cards.map(u32::from),
+            red_cards: statistics.red_cards.map(u32::from),
+            substituted_in: statistics.substituted_in.map(u32::from),
+            substituted_out: statistics.substituted_out.map(u32::from),
+            goals_by_penalty: statistics.goals_by_penalty.map(u32::from),
+            penalties_missed: statistics.penalties_missed.map(u32::from),
+            own_goals: statistics.own_goals.map(u32::from),
+            offsides: statistics.offsides.map(u32::from),
+            corner_kicks: statistics.corner_kicks.map(u32::from),
+        }
     }
 }
 
+/// Temporary struct to hold player id and stat value for sorting purposes in
+/// BTreeSet
 #[derive(Debug, Eq, PartialEq)]
-struct PlayerWithAssists {
+struct PlayerWithStat {
     id: String,
-    assists: u32,
+    value: u32,
 }
 
-impl Ord for PlayerWithAssists {
+impl Ord for PlayerWithStat {
     fn cmp(&self, other: &Self) -> Ordering {
         other
-            .assists
-            .cmp(&self.assists)
+            .value
+            .cmp(&self.value)
             .then_with(|| self.id.cmp(&other.id))
     }
 }
 
-impl PartialOrd for PlayerWithAssists {
+impl PartialOrd for PlayerWithStat {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
@@ -193,6 +809,7 @@ mod tests {
             name: "Player 1".to_string(),
             goals_scored: 10,
             assists: 5,
+            ..Default::default()
         };
 
         let player2 = Player {
@@ -200,21 +817,25 @@ mod tests {
             name: "Player 2".to_string(),
             goals_scored: 5,
             assists: 10,
+            ..Default::default()
         };
 
         player_db.add_player(player1);
         player_db.add_player(player2);
 
-        player_db.index_players();
+        let top_scorers =
+            player_db.leaderboard(StatKey::Goals, LEADERBOARD_LIMIT, TiePolicy::Strict);
+        let top_assists =
+            player_db.leaderboard(StatKey::Assists, LEADERBOARD_LIMIT, TiePolicy::Strict);
 
-        assert_eq!(player_db.top_scorers.len(), 2);
-        assert_eq!(player_db.top_assists.len(), 2);
+        assert_eq!(top_scorers.entries.len(), 2);
+        assert_eq!(top_assists.entries.len(), 2);
 
-        assert_eq!(player_db.top_scorers[0], "1");
-        assert_eq!(player_db.top_assists[0], "2");
+        assert_eq!(top_scorers.entries[0].id, "1");
+        assert_eq!(top_assists.entries[0].id, "2");
     }
 
-    // test player db limits top scorers and top assists to 10
+    // test player db limits leaderboards to the limit
     #[test]
     fn test_player_db_limit() {
         let mut player_db = PlayerDB::new();
@@ -223,16 +844,531 @@ mod tests {
             let player = Player {
                 id: i.to_string(),
                 name: format!("Player {}", i),
-                goals_scored: i as u16,
-                assists: i as u16,
+                goals_scored: i as u32,
+                assists: i as u32,
+                ..Default::default()
             };
 
             player_db.add_player(player);
         }
 
-        player_db.index_players();
+        let top_scorers =
+            player_db.leaderboard(StatKey::Goals, LEADERBOARD_LIMIT, TiePolicy::Strict);
+        let top_assists =
+            player_db.leaderboard(StatKey::Assists, LEADERBOARD_LIMIT, TiePolicy::Strict);
+
+        assert_eq!(top_scorers.entries.len(), 10);
+        assert_eq!(top_assists.entries.len(), 10);
+
+        let top_scorers = player_db.leaderboard(StatKey::Goals, 5, TiePolicy::Include);
+
+        assert_eq!(top_scorers.entries.len(), 5);
+    }
+
+    // create a player db where players 0..4 scored 5, 3, 3, 3, 1 goals
+    fn tied_player_db() -> PlayerDB {
+        let mut player_db = PlayerDB::new();
+
+        for (i, goals) in [5, 3, 3, 3, 1].into_iter().enumerate() {
+            player_db.add_player(Player {
+                id: i.to_string(),
+                name: format!("Player {}", i),
+                goals_scored: goals,
+                assists: 0,
+                ..Default::default()
+            });
+        }
+
+        player_db
+    }
+
+    fn ranks(leaderboard: &Leaderboard) -> Vec<usize> {
+        leaderboard.entries.iter().map(|p| p.rank).collect()
+    }
+
+    fn ids(leaderboard: &Leaderboard) -> Vec<&str> {
+        leaderboard.entries.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    // test strict policy cuts tied players at the limit
+    #[test]
+    fn test_player_db_ties_strict() {
+        let player_db = tied_player_db();
+
+        let top_scorers = player_db.leaderboard(StatKey::Goals, 3, TiePolicy::Strict);
+
+        assert_eq!(ranks(&top_scorers), vec![1, 2, 2]);
+    }
+
+    // test include policy keeps every player tied with the last place
+    #[test]
+    fn test_player_db_ties_include() {
+        let player_db = tied_player_db();
+
+        let top_scorers = player_db.leaderboard(StatKey::Goals, 3, TiePolicy::Include);
+
+        assert_eq!(ranks(&top_scorers), vec![1, 2, 2, 2]);
+    }
+
+    // test leaderboards rank by any stat and keep the stat value
+    #[test]
+    fn test_player_db_leaderboard_stat() {
+        let mut player_db = PlayerDB::new();
+
+        for (i, (goals, assists)) in [(10, 0), (4, 8), (6, 2)].into_iter().enumerate() {
+            player_db.add_player(Player {
+                id: i.to_string(),
+                name: format!("Player {}", i),
+                goals_scored: goals,
+                assists,
+                ..Default::default()
+            });
+        }
+
+        let leaderboard = player_db.leaderboard(
+            StatKey::GoalContributions,
+            LEADERBOARD_LIMIT,
+            TiePolicy::Strict,
+        );
+
+        let ids: Vec<&str> = leaderboard.entries.iter().map(|p| p.id.as_str()).collect();
+        let values: Vec<u32> = leaderboard.entries.iter().map(|p| p.value).collect();
+
+        assert_eq!(ids, vec!["1", "0", "2"]);
+        assert_eq!(values, vec![12, 10, 8]);
+        assert_eq!(ranks(&leaderboard), vec![1, 2, 3]);
+    }
+
+    // test competition policy skips ranks after tied players
+    #[test]
+    fn test_player_db_ties_competition() {
+        let player_db = tied_player_db();
+
+        let top_scorers = player_db.leaderboard(StatKey::Goals, 5, TiePolicy::Competition);
+
+        assert_eq!(ranks(&top_scorers), vec![1, 2, 2, 2, 5]);
+    }
+
+    // test the statistics of the payload are carried into players, missing
+    // ones counting as zero in leaderboards
+    #[test]
+    fn test_player_db_statistics() {
+        let data: ApiSeasonCompetitorStatistics = serde_json::from_str(
+            r#"{"competitor": {
+                "id": "sr:competitor:17",
+                "name": "Manchester City",
+                "statistics": {"goals_scored": 96},
+                "players": [
+                    {"id": "1", "name": "Player 1", "statistics": {
+                        "goals_scored": 27, "assists": 5, "minutes_played": 2553,
+                        "shots_on_target": 50, "shots_off_target": 30, "shots_blocked": 12,
+                        "yellow_cards": 1, "red_cards": 0, "yellow_red_cards": 1
+                    }},
+                    {"id": "2", "name": "Player 2", "statistics": {
+                        "goals_scored": 3, "assists": 1, "yellow_cards": 6
+                    }}
+                ]
+            }}"#,
+        )
+        .unwrap();
+
+        let mut player_db = PlayerDB::new();
+        player_db.add_competitor_statistics(&data);
+
+        let player = &player_db.players["1"];
+        assert_eq!(player.to_string(), "Player 1 (Manchester City)");
+        assert_eq!(player.statistics.minutes_played, Some(2553));
+        assert_eq!(StatKey::Shots.value(player), 92);
+        assert_eq!(StatKey::RedCards.value(player), 1);
+
+        let player = &player_db.players["2"];
+        assert_eq!(player.statistics.minutes_played, None);
+        assert_eq!(StatKey::Minutes.value(player), 0);
+
+        let leaderboard =
+            player_db.leaderboard(StatKey::YellowCards, LEADERBOARD_LIMIT, TiePolicy::Strict);
+
+        assert_eq!(leaderboard.entries[0].id, "2");
+        assert_eq!(leaderboard.entries[0].value, 6);
+    }
+
+    // create a player db of players with minutes, goals, assists and shots
+    fn minutes_player_db() -> PlayerDB {
+        let mut player_db = PlayerDB::new();
+
+        for (i, minutes, goals, assists, shots) in [
+            (0, Some(10), 1, 0, 1),
+            (1, Some(900), 10, 2, 40),
+            (2, Some(1800), 15, 10, 30),
+            (3, None, 30, 0, 60),
+        ] {
+            player_db.add_player(Player {
+                id: i.to_string(),
+                name: format!("Player {}", i),
+                goals_scored: goals,
+                assists,
+                statistics: PlayerStatistics {
+                    minutes_played: minutes,
+                    shots_on_target: Some(shots),
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+        }
+
+        player_db
+    }
+
+    fn values(leaderboard: &Leaderboard<Metric>) -> Vec<(&str, f64)> {
+        leaderboard
+            .entries
+            .iter()
+            .map(|p| (p.id.as_str(), p.value))
+            .collect()
+    }
+
+    // test per 90 metrics leave out players under the minimum minutes
+    #[test]
+    fn test_player_db_metric_min_minutes() {
+        let player_db = minutes_player_db();
+
+        let goals_per_90 = player_db.metric_leaderboard(
+            Metric::GoalsPer90,
+            LEADERBOARD_LIMIT,
+            TiePolicy::Include,
+            MIN_MINUTES,
+        );
+
+        assert_eq!(values(&goals_per_90), vec![("1", 1.0), ("2", 0.75)]);
+
+        // without a threshold the 10 minute cameo tops the chart
+        let goals_per_90 = player_db.metric_leaderboard(
+            Metric::GoalsPer90,
+            LEADERBOARD_LIMIT,
+            TiePolicy::Include,
+            0,
+        );
+
+        assert_eq!(goals_per_90.entries[0].id, "0");
+        assert_eq!(goals_per_90.entries[0].value, 9.0);
+    }
+
+    // test efficiency metrics and their ordering
+    #[test]
+    fn test_player_db_metrics() {
+        let player_db = minutes_player_db();
+        let leaderboard = |metric| {
+            player_db.metric_leaderboard(metric, LEADERBOARD_LIMIT, TiePolicy::Include, MIN_MINUTES)
+        };
+
+        assert_eq!(
+            values(&leaderboard(Metric::GoalContributionsPer90)),
+            vec![("2", 1.25), ("1", 1.2)]
+        );
+        assert_eq!(
+            values(&leaderboard(Metric::ShotConversion)),
+            vec![("2", 50.0), ("1", 25.0)]
+        );
+        // fewer minutes per goal ranks first
+        assert_eq!(
+            values(&leaderboard(Metric::MinutesPerGoal)),
+            vec![("1", 90.0), ("2", 120.0)]
+        );
+    }
+
+    // test competitor profiles add details to players already in the database
+    #[test]
+    fn test_player_db_competitor_profile() {
+        let mut player_db = PlayerDB::new();
+
+        player_db.add_player(Player {
+            id: "sr:player:1".to_string(),
+            name: "Haaland, Erling".to_string(),
+            goals_scored: 27,
+            stints: vec![PlayerStint {
+                competitor_id: "sr:competitor:17".to_string(),
+                competitor_name: "Manchester City".to_string(),
+                goals_scored: 27,
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+
+        let profile: ApiCompetitorProfile = serde_json::from_str(
+            r#"{
+                "competitor": {"id": "sr:competitor:17", "name": "Manchester City"},
+                "players": [
+                    {"id": "sr:player:1", "name": "Haaland, Erling", "type": "forward",
+                     "nationality": "Norway", "date_of_birth": "2000-07-21"},
+                    {"id": "sr:player:2", "name": "Ederson", "type": "goalkeeper"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        player_db.add_competitor_profile(&profile);
+
+        let player = &player_db.players["sr:player:1"];
+        assert_eq!(player.position.as_deref(), Some("forward"));
+        assert_eq!(player.nationality.as_deref(), Some("Norway"));
+        assert_eq!(player.date_of_birth, NaiveDate::from_ymd_opt(2000, 7, 21));
+        assert_eq!(
+            player.to_string(),
+            "Haaland, Erling (Manchester City) [forward, Norway, born 2000-07-21]"
+        );
+
+        // players without statistics are not added
+        assert_eq!(player_db.players.len(), 1);
+        assert_eq!(
+            player_db.competitors().into_iter().collect::<Vec<_>>(),
+            [("sr:competitor:17", "Manchester City")]
+        );
+    }
+
+    fn squad_statistics() -> ApiSeasonCompetitorStatistics {
+        serde_json::from_str(
+            r#"{"competitor": {
+                "id": "sr:competitor:17",
+                "name": "Manchester City",
+                "statistics": {"goals_scored": 30},
+                "players": [
+                    {"id": "1", "name": "Striker", "statistics": {"goals_scored": 27, "assists": 5}},
+                    {"id": "2", "name": "Playmaker", "statistics": {"goals_scored": 0, "assists": 12}},
+                    {"id": "3", "name": "Defender", "statistics": {"yellow_cards": 4}},
+                    {"id": "4", "name": "Third Keeper", "statistics": {"goals_scored": 0, "assists": 0}}
+                ]
+            }}"#,
+        )
+        .unwrap()
+    }
+
+    // test assist leaders with zero goals are ranked
+    #[test]
+    fn test_player_db_assists_without_goals() {
+        let mut player_db = PlayerDB::new();
+        player_db.add_competitor_statistics(&squad_statistics());
+
+        let top_assists =
+            player_db.leaderboard(StatKey::Assists, LEADERBOARD_LIMIT, TiePolicy::Include);
+
+        assert_eq!(top_assists.entries[0].id, "2");
+        assert_eq!(top_assists.entries[0].value, 12);
+
+        // the playmaker is kept for the assists, not listed as a scorer
+        let top_scorers =
+            player_db.leaderboard(StatKey::Goals, LEADERBOARD_LIMIT, TiePolicy::Include);
+
+        assert_eq!(ids(&top_scorers), ["1"]);
+    }
+
+    // test players without a sparse stat are left out of its leaderboard
+    #[test]
+    fn test_player_db_sparse_stat() {
+        let mut player_db = PlayerDB::with_ingest_policy(IngestPolicy::All);
+        player_db.add_competitor_statistics(&squad_statistics());
+
+        let yellow_cards =
+            player_db.leaderboard(StatKey::YellowCards, LEADERBOARD_LIMIT, TiePolicy::Include);
+        let red_cards =
+            player_db.leaderboard(StatKey::RedCards, LEADERBOARD_LIMIT, TiePolicy::Include);
+
+        assert_eq!(ids(&yellow_cards), ["3"]);
+        assert!(red_cards.entries.is_empty());
+    }
+
+    // test the ingest policy decides which players are added
+    #[test]
+    fn test_player_db_ingest_policy() {
+        let mut player_db = PlayerDB::new();
+        player_db.add_competitor_statistics(&squad_statistics());
+
+        let mut ids: Vec<&str> = player_db.players.keys().map(String::as_str).collect();
+        ids.sort();
+
+        assert_eq!(ids, ["1", "2", "3"]);
+
+        let mut player_db = PlayerDB::with_ingest_policy(IngestPolicy::All);
+        player_db.add_competitor_statistics(&squad_statistics());
+
+        assert_eq!(player_db.players.len(), 4);
+    }
+
+    fn competitor_statistics(
+        id: &str,
+        name: &str,
+        goals: u16,
+        minutes: u16,
+    ) -> ApiSeasonCompetitorStatistics {
+        serde_json::from_str(&format!(
+            r#"{{"competitor": {{
+                "id": "{id}",
+                "name": "{name}",
+                "statistics": {{"goals_scored": 40}},
+                "players": [
+                    {{"id": "sr:player:1", "name": "Transfer", "statistics": {{
+                        "goals_scored": {goals}, "assists": 1, "minutes_played": {minutes}
+                    }}}},
+                    {{"id": "sr:player:{id}", "name": "Teammate", "statistics": {{"goals_scored": 9}}}}
+                ]
+            }}}}"#
+        ))
+        .unwrap()
+    }
+
+    // test a player transferred mid-season is ranked by the combined stats
+    #[test]
+    fn test_player_db_transfer() {
+        let mut player_db = PlayerDB::new();
+
+        player_db.add_competitor_statistics(&competitor_statistics(
+            "sr:competitor:1",
+            "Team 1",
+            6,
+            900,
+        ));
+        player_db.add_competitor_statistics(&competitor_statistics(
+            "sr:competitor:2",
+            "Team 2",
+            5,
+            800,
+        ));
+
+        let player = &player_db.players["sr:player:1"];
+
+        assert_eq!(player.goals_scored, 11);
+        assert_eq!(player.assists, 2);
+        assert_eq!(player.statistics.minutes_played, Some(1700));
+        assert_eq!(player.stints.len(), 2);
+        assert_eq!(player.stints[1].goals_scored, 5);
+        assert_eq!(player.to_string(), "Transfer (Team 1, Team 2)");
+
+        let top_scorers = player_db.leaderboard(StatKey::Goals, 1, TiePolicy::Strict);
+
+        assert_eq!(top_scorers.entries[0].id, "sr:player:1");
+        assert_eq!(top_scorers.entries[0].value, 11);
+
+        // statistics of a competitor fetched again replace the old ones
+        player_db.add_competitor_statistics(&competitor_statistics(
+            "sr:competitor:2",
+            "Team 2",
+            7,
+            900,
+        ));
+
+        let player = &player_db.players["sr:player:1"];
+
+        assert_eq!(player.goals_scored, 13);
+        assert_eq!(player.stints.len(), 2);
+    }
+
+    // test players of several competitions are combined, keeping the
+    // competitions they played in
+    #[test]
+    fn test_player_db_competitions() {
+        let season = |id: &str, name: &str| ApiCompetitionSeason {
+            id: id.to_string(),
+            name: name.to_string(),
+            year: "23/24".to_string(),
+            start_date: NaiveDate::from_ymd_opt(2023, 8, 11).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 5, 19).unwrap(),
+        };
+
+        let mut league = PlayerDB::new();
+        league.add_competitor_statistics(&competitor_statistics(
+            "sr:competitor:1",
+            "Team 1",
+            6,
+            900,
+        ));
+        league.set_season(&season("sr:season:1", "Premier League 23/24"));
+
+        let mut cup = PlayerDB::new();
+        cup.add_competitor_statistics(&competitor_statistics("sr:competitor:1", "Team 1", 2, 300));
+        cup.set_season(&season("sr:season:2", "UEFA Champions League 23/24"));
+
+        let mut player_db = PlayerDB::new();
+        player_db.extend(league);
+        player_db.extend(cup);
+
+        let player = &player_db.players["sr:player:1"];
+
+        assert_eq!(player.goals_scored, 8);
+        assert_eq!(player.statistics.minutes_played, Some(1200));
+        assert_eq!(player.stints.len(), 2);
+        assert_eq!(player.teams(), ["Team 1"]);
+        assert_eq!(
+            player.competitions(),
+            ["Premier League 23/24", "UEFA Champions League 23/24"]
+        );
+
+        let mut output = Output::new(LEADERBOARD_COLUMNS);
+        let top_scorers = player_db.leaderboard(StatKey::Goals, 1, TiePolicy::Strict);
+
+        player_db.write_leaderboard(&top_scorers, &mut output);
+
+        assert_eq!(
+            output.rows()[0][5],
+            "Premier League 23/24, UEFA Champions League 23/24"
+        );
+    }
+
+    // test totals over many seasons don't overflow the per-season stats
+    #[test]
+    fn test_player_db_long_career_totals() {
+        let mut player_db = PlayerDB::new();
+
+        for i in 0..3 {
+            let mut season_db = PlayerDB::new();
+            season_db.add_competitor_statistics(&competitor_statistics(
+                "sr:competitor:1",
+                "Team 1",
+                30,
+                30000,
+            ));
+            season_db.set_season(&ApiCompetitionSeason {
+                id: format!("sr:season:{}", i),
+                name: format!("Season {}", i),
+                year: i.to_string(),
+                start_date: NaiveDate::from_ymd_opt(2000 + i, 8, 1).unwrap(),
+                end_date: NaiveDate::from_ymd_opt(2001 + i, 5, 31).unwrap(),
+            });
+
+            player_db.extend(season_db);
+        }
+
+        let player = &player_db.players["sr:player:1"];
+
+        assert_eq!(player.statistics.minutes_played, Some(90000));
+        assert_eq!(StatKey::Minutes.value(player), 90000);
+        assert_eq!(
+            Metric::GoalsPer90.value(player, MIN_MINUTES),
+            Some(90.0 * 90.0 / 90000.0)
+        );
+    }
+
+    // test players are found by id or part of their name
+    #[test]
+    fn test_player_db_find_players() {
+        let mut player_db = PlayerDB::new();
+
+        player_db.add_competitor_statistics(&competitor_statistics(
+            "sr:competitor:1",
+            "Team 1",
+            6,
+            900,
+        ));
+
+        let names = |query| {
+            player_db
+                .find_players(query)
+                .map(|players| players.iter().map(|p| p.name.as_str()).collect::<Vec<_>>())
+        };
 
-        assert_eq!(player_db.top_scorers.len(), 10);
-        assert_eq!(player_db.top_assists.len(), 10);
+        assert_eq!(names("SR:PLAYER:1").unwrap(), ["Transfer"]);
+        assert_eq!(names("t").unwrap(), ["Teammate", "Transfer"]);
+        assert_eq!(
+            names("Haaland").unwrap_err().to_string(),
+            "No player matching \"Haaland\" found"
+        );
     }
 }
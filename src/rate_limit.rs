@@ -0,0 +1,129 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tokio::time::sleep;
+
+use crate::error::{Error, Result};
+
+/// Lowest rate limit allowed, one request every 1000 seconds. Lower rates
+/// would wait longer than a [`Duration`] can hold
+pub const MIN_RATE_LIMIT: f64 = 0.001;
+
+/// Token bucket rate limiter shared by every request made to the API, so
+/// concurrent requests never exceed the account's queries per second
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Create a new [`RateLimiter`] allowing `requests_per_second` on
+    /// average, and up to `burst` requests at once. The rate must be a
+    /// finite number, at least [`MIN_RATE_LIMIT`]
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self> {
+        if !requests_per_second.is_finite() || requests_per_second < MIN_RATE_LIMIT {
+            return Err(Error::Config(format!(
+                "the rate limit must be at least {} requests per second, got {}",
+                MIN_RATE_LIMIT, requests_per_second
+            )));
+        }
+
+        let burst = burst.max(1) as f64;
+
+        Ok(Self {
+            requests_per_second,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Create a new [`RateLimiter`] for the limits of a Sportradar account
+    /// access level. Trial accounts are limited to 1 query per second
+    pub fn for_access_level(access_level: &str) -> Self {
+        let (requests_per_second, burst) = match access_level {
+            "trial" => (1.0, 1),
+            _ => (10.0, 10),
+        };
+
+        Self::new(requests_per_second, burst).expect("Invalid access level rate limit")
+    }
+
+    /// Wait until a request is allowed to be made
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().unwrap();
+
+                // refill the tokens for the time elapsed since the last refill
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+                bucket.tokens =
+                    (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+                bucket.refilled_at = now;
+
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+
+                    return;
+                }
+
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.requests_per_second)
+            };
+
+            sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test requests within the burst are not delayed
+    #[tokio::test]
+    async fn test_rate_limiter_burst() {
+        let rate_limiter = RateLimiter::new(1.0, 3).unwrap();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            rate_limiter.acquire().await;
+        }
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    // test requests over the burst wait for tokens to refill
+    #[tokio::test]
+    async fn test_rate_limiter_rate() {
+        let rate_limiter = RateLimiter::new(20.0, 1).unwrap();
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            rate_limiter.acquire().await;
+        }
+
+        // 4 requests over the burst at 20 requests per second
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+
+    // test rates that would never let a request through are rejected
+    #[test]
+    fn test_rate_limiter_invalid_rate() {
+        for rate in [0.0, -1.0, 1e-300, f64::NAN, f64::INFINITY] {
+            assert!(matches!(RateLimiter::new(rate, 1), Err(Error::Config(_))));
+        }
+    }
+}
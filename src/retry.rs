@@ -0,0 +1,142 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Utc};
+
+/// How failed requests are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled on every retry
+    pub base_delay: Duration,
+    /// Upper bound of the delay between retries
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a new [`RetryPolicy`] retrying up to `max_retries` times with
+    /// the default delays
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `attempt`, starting at 0. The delay grows
+    /// exponentially and half of it is randomized, so concurrent requests that
+    /// failed together don't retry together
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+
+        delay / 2 + delay.mul_f64(random_fraction() / 2.0)
+    }
+
+    /// Delay before retry number `attempt`, honouring the server's
+    /// `Retry-After` when there is one. `None` when the server asks to wait
+    /// longer than the max delay, such requests are not retried
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        match retry_after {
+            Some(retry_after) if retry_after > self.max_delay => None,
+            Some(retry_after) => Some(retry_after),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+/// Parse a `Retry-After` header value, either in seconds or as an HTTP date
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+
+    Some(
+        (date.with_timezone(&Utc) - Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Random number between 0 and 1, good enough for jitter
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test backoff grows exponentially, with jitter, up to the max delay
+    #[test]
+    fn test_backoff() {
+        let retry_policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+        };
+
+        for (attempt, delay) in [(0, 1), (1, 2), (2, 4), (3, 5), (10, 5)] {
+            let delay = Duration::from_secs(delay);
+            let backoff = retry_policy.backoff(attempt);
+
+            assert!(backoff >= delay / 2 && backoff <= delay, "{:?}", backoff);
+        }
+    }
+
+    // test Retry-After is honoured up to the max delay
+    #[test]
+    fn test_delay() {
+        let retry_policy = RetryPolicy::default();
+
+        assert_eq!(
+            retry_policy.delay(0, Some(Duration::from_secs(2))),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            retry_policy.delay(0, Some(retry_policy.max_delay)),
+            Some(retry_policy.max_delay)
+        );
+        assert_eq!(retry_policy.delay(0, Some(Duration::from_secs(3600))), None);
+        assert!(retry_policy.delay(0, None).unwrap() <= retry_policy.base_delay);
+    }
+
+    // test Retry-After is parsed in seconds and as a date
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("3"), Some(Duration::from_secs(3)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,386 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use chrono::NaiveDate;
+
+use crate::api::ApiCompetitionSeason;
+
+/// Selects one season out of the seasons of a competition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeasonSelector {
+    /// The season in progress today, falling back to the last one that started
+    Current,
+    /// The season with the most recent start date
+    Latest,
+    /// A season id, e.g. `sr:season:105353`
+    Id(String),
+    /// A season display name, e.g. `Premier League 23/24`
+    Name(String),
+    /// A season year, e.g. `23/24` or `2024`
+    Year(String),
+    /// The `n` most recent seasons up to the current one, e.g. `last-3`
+    Last(usize),
+}
+
+impl SeasonSelector {
+    /// Find the season matching the selector, using `today` to resolve
+    /// [`SeasonSelector::Current`]. [`SeasonSelector::Last`] matches several
+    /// seasons, only the current one is returned, use
+    /// [`SeasonSelector::select_all`] to get all of them
+    pub fn select<'a>(
+        &self,
+        seasons: &'a [ApiCompetitionSeason],
+        today: NaiveDate,
+    ) -> Result<&'a ApiCompetitionSeason, SeasonNotFound> {
+        let season = match self {
+            // a single season of the last ones is the current one
+            SeasonSelector::Current | SeasonSelector::Last(_) => seasons
+                .iter()
+                .filter(|s| s.start_date <= today && today <= s.end_date)
+                .max_by_key(|s| s.start_date)
+                .or_else(|| {
+                    // in between seasons, pick the one that finished last
+                    seasons
+                        .iter()
+                        .filter(|s| s.start_date <= today)
+                        .max_by_key(|s| s.start_date)
+                })
+                .or_else(|| seasons.iter().max_by_key(|s| s.start_date)),
+            SeasonSelector::Latest => seasons.iter().max_by_key(|s| s.start_date),
+            SeasonSelector::Id(id) => seasons.iter().find(|s| &s.id == id),
+            SeasonSelector::Name(name) => seasons
+                .iter()
+                .find(|s| s.name.eq_ignore_ascii_case(name.trim())),
+            SeasonSelector::Year(year) => seasons
+                .iter()
+                .filter(|s| &s.year == year)
+                .max_by_key(|s| s.start_date),
+        };
+
+        season.ok_or_else(|| self.not_found(seasons))
+    }
+
+    /// Find every season matching the selector, oldest first. Only
+    /// [`SeasonSelector::Last`] matches more than one season
+    pub fn select_all<'a>(
+        &self,
+        seasons: &'a [ApiCompetitionSeason],
+        today: NaiveDate,
+    ) -> Result<Vec<&'a ApiCompetitionSeason>, SeasonNotFound> {
+        let SeasonSelector::Last(n) = self else {
+            return self.select(seasons, today).map(|season| vec![season]);
+        };
+
+        // leave out the seasons that haven't started yet, unless none has
+        let mut last: Vec<&ApiCompetitionSeason> =
+            match seasons.iter().any(|s| s.start_date <= today) {
+                true => seasons.iter().filter(|s| s.start_date <= today).collect(),
+                false => seasons.iter().collect(),
+            };
+
+        last.sort_by_key(|s| s.start_date);
+        last.drain(..last.len().saturating_sub(*n));
+
+        if last.is_empty() {
+            return Err(self.not_found(seasons));
+        }
+
+        Ok(last)
+    }
+
+    /// Create the error returned when no season matches the selector
+    fn not_found(&self, seasons: &[ApiCompetitionSeason]) -> SeasonNotFound {
+        SeasonNotFound {
+            selector: self.to_string(),
+            available: seasons
+                .iter()
+                .map(|s| format!("{} ({}, {})", s.name, s.id, s.year))
+                .collect(),
+        }
+    }
+}
+
+impl FromStr for SeasonSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err("season can not be empty".into());
+        }
+
+        let selector = match s.to_ascii_lowercase().as_str() {
+            "current" => SeasonSelector::Current,
+            "latest" => SeasonSelector::Latest,
+            last if last.starts_with("last-") => match last["last-".len()..].parse() {
+                Ok(n) if n > 0 => SeasonSelector::Last(n),
+                _ => return Err("expected a number of seasons after `last-`, e.g. `last-3`".into()),
+            },
+            _ if s.starts_with("sr:season:") => SeasonSelector::Id(s.into()),
+            _ if s
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '/' || c == '-') =>
+            {
+                SeasonSelector::Year(s.into())
+            },
+            _ => SeasonSelector::Name(s.into()),
+        };
+
+        Ok(selector)
+    }
+}
+
+impl fmt::Display for SeasonSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeasonSelector::Current => write!(f, "current"),
+            SeasonSelector::Latest => write!(f, "latest"),
+            SeasonSelector::Last(n) => write!(f, "last-{}", n),
+            SeasonSelector::Id(s) | SeasonSelector::Name(s) | SeasonSelector::Year(s) => {
+                write!(f, "{}", s)
+            },
+        }
+    }
+}
+
+/// A competition along with the season to get stats for, parsed from
+/// `<competition id>[@<season>]`, e.g. `sr:competition:8@23/24`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompetitionSelector {
+    pub competition_id: String,
+    /// Season of the competition, the default season is used when missing
+    pub season: Option<SeasonSelector>,
+}
+
+impl CompetitionSelector {
+    /// Get the season selector of the competition, falling back to `default`
+    pub fn season<'a>(&'a self, default: &'a SeasonSelector) -> &'a SeasonSelector {
+        self.season.as_ref().unwrap_or(default)
+    }
+}
+
+impl FromStr for CompetitionSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (competition_id, season) = match s.split_once('@') {
+            Some((competition_id, season)) => (competition_id, Some(season.parse()?)),
+            None => (s, None),
+        };
+
+        let competition_id = competition_id.trim();
+
+        if competition_id.is_empty() {
+            return Err("competition id can not be empty".into());
+        }
+
+        Ok(Self {
+            competition_id: competition_id.into(),
+            season,
+        })
+    }
+}
+
+impl fmt::Display for CompetitionSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.season {
+            Some(season) => write!(f, "{}@{}", self.competition_id, season),
+            None => write!(f, "{}", self.competition_id),
+        }
+    }
+}
+
+/// Error returned when no season matches a [`SeasonSelector`]
+#[derive(Debug)]
+pub struct SeasonNotFound {
+    pub selector: String,
+    pub available: Vec<String>,
+}
+
+impl fmt::Display for SeasonNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.available.is_empty() {
+            return write!(
+                f,
+                "No season matching \"{}\" found, the competition has no seasons",
+                self.selector
+            );
+        }
+
+        write!(
+            f,
+            "No season matching \"{}\" found, available seasons: {}",
+            self.selector,
+            self.available.join(", ")
+        )
+    }
+}
+
+impl Error for SeasonNotFound {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn season(id: &str, name: &str, year: &str, start: &str, end: &str) -> ApiCompetitionSeason {
+        ApiCompetitionSeason {
+            id: id.to_string(),
+            name: name.to_string(),
+            year: year.to_string(),
+            start_date: NaiveDate::parse_from_str(start, "%Y-%m-%d").unwrap(),
+            end_date: NaiveDate::parse_from_str(end, "%Y-%m-%d").unwrap(),
+        }
+    }
+
+    fn seasons() -> Vec<ApiCompetitionSeason> {
+        vec![
+            season(
+                "sr:season:1",
+                "Premier League 22/23",
+                "22/23",
+                "2022-08-05",
+                "2023-05-28",
+            ),
+            season(
+                "sr:season:2",
+                "Premier League 23/24",
+                "23/24",
+                "2023-08-11",
+                "2024-05-19",
+            ),
+            season(
+                "sr:season:3",
+                "Premier League 24/25",
+                "24/25",
+                "2024-08-16",
+                "2025-05-25",
+            ),
+        ]
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    // test season selectors are parsed from strings
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!("current".parse(), Ok(SeasonSelector::Current));
+        assert_eq!("LATEST".parse(), Ok(SeasonSelector::Latest));
+        assert_eq!(
+            "sr:season:2".parse(),
+            Ok(SeasonSelector::Id("sr:season:2".into()))
+        );
+        assert_eq!("23/24".parse(), Ok(SeasonSelector::Year("23/24".into())));
+        assert_eq!(
+            "Premier League 23/24".parse(),
+            Ok(SeasonSelector::Name("Premier League 23/24".into()))
+        );
+        assert!("".parse::<SeasonSelector>().is_err());
+        assert_eq!("Last-3".parse(), Ok(SeasonSelector::Last(3)));
+        assert!("last-0".parse::<SeasonSelector>().is_err());
+        assert!("last-x".parse::<SeasonSelector>().is_err());
+    }
+
+    // test competitions are parsed with an optional season
+    #[test]
+    fn test_parse_competition_selector() {
+        let competition: CompetitionSelector = "sr:competition:8@23/24".parse().unwrap();
+
+        assert_eq!(competition.competition_id, "sr:competition:8");
+        assert_eq!(
+            competition.season(&SeasonSelector::Current),
+            &SeasonSelector::Year("23/24".into())
+        );
+        assert_eq!(competition.to_string(), "sr:competition:8@23/24");
+
+        let competition: CompetitionSelector = " sr:competition:17 ".parse().unwrap();
+
+        assert_eq!(competition.season, None);
+        assert_eq!(
+            competition.season(&SeasonSelector::Latest),
+            &SeasonSelector::Latest
+        );
+        assert!("@23/24".parse::<CompetitionSelector>().is_err());
+        assert!("sr:competition:8@".parse::<CompetitionSelector>().is_err());
+    }
+
+    // test seasons are selected by id, name and year
+    #[test]
+    fn test_select_season() {
+        let seasons = seasons();
+        let today = date("2024-01-01");
+
+        let select = |s: &str| {
+            s.parse::<SeasonSelector>()
+                .unwrap()
+                .select(&seasons, today)
+                .map(|s| s.id.clone())
+        };
+
+        assert_eq!(select("sr:season:1").unwrap(), "sr:season:1");
+        assert_eq!(select("premier league 23/24").unwrap(), "sr:season:2");
+        assert_eq!(select("24/25").unwrap(), "sr:season:3");
+        assert_eq!(select("latest").unwrap(), "sr:season:3");
+        assert_eq!(select("current").unwrap(), "sr:season:2");
+    }
+
+    // test current season falls back to the last started season in between
+    // seasons
+    #[test]
+    fn test_select_current_season_off_season() {
+        let seasons = seasons();
+
+        let season = SeasonSelector::Current
+            .select(&seasons, date("2023-07-01"))
+            .unwrap();
+        assert_eq!(season.id, "sr:season:1");
+
+        let season = SeasonSelector::Current
+            .select(&seasons, date("2020-01-01"))
+            .unwrap();
+        assert_eq!(season.id, "sr:season:3");
+    }
+
+    // test the last seasons are selected up to the current one, oldest first
+    #[test]
+    fn test_select_last_seasons() {
+        let seasons = seasons();
+
+        let select = |n, today| {
+            SeasonSelector::Last(n)
+                .select_all(&seasons, date(today))
+                .unwrap()
+                .iter()
+                .map(|s| s.id.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(select(2, "2024-01-01"), ["sr:season:1", "sr:season:2"]);
+        assert_eq!(
+            select(5, "2025-01-01"),
+            ["sr:season:1", "sr:season:2", "sr:season:3"]
+        );
+        assert_eq!(select(1, "2020-01-01"), ["sr:season:3"]);
+
+        let current = SeasonSelector::Current
+            .select_all(&seasons, date("2024-01-01"))
+            .unwrap();
+
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].id, "sr:season:2");
+    }
+
+    // test unknown seasons report the available seasons
+    #[test]
+    fn test_season_not_found() {
+        let seasons = seasons();
+
+        let err = SeasonSelector::Year("19/20".into())
+            .select(&seasons, date("2024-01-01"))
+            .unwrap_err();
+
+        assert_eq!(err.available.len(), 3);
+        assert!(err.to_string().contains("Premier League 23/24"));
+    }
+}
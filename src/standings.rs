@@ -0,0 +1,153 @@
+use clap::ValueEnum;
+
+use crate::api::{ApiSeasonStandings, ApiStandingGroup, ApiStandingRow};
+
+/// Which matches the standings are computed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum StandingType {
+    /// Every match
+    #[default]
+    Total,
+    /// Matches played at home
+    Home,
+    /// Matches played away
+    Away,
+}
+
+impl StandingType {
+    /// Name of the standing type in the API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StandingType::Total => "total",
+            StandingType::Home => "home",
+            StandingType::Away => "away",
+        }
+    }
+}
+
+/// Get the groups of the standings of a type. Leagues have a single group,
+/// while cups have one per group stage group
+pub fn standing_groups(
+    standings: &ApiSeasonStandings,
+    standing_type: StandingType,
+) -> &[ApiStandingGroup] {
+    standings
+        .standings
+        .iter()
+        .find(|standing| standing.standing_type == standing_type.as_str())
+        .map(|standing| standing.groups.as_slice())
+        .unwrap_or_default()
+}
+
+/// Format the standings of a group as a table, with a header line
+pub fn standings_table(group: &ApiStandingGroup) -> Vec<String> {
+    let width = group
+        .standings
+        .iter()
+        .map(|row| row.competitor.name.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max("Team".len());
+
+    let mut lines = vec![format!(
+        "{:>3}  {:<width$}  {:>2}  {:>2}  {:>2}  {:>2}  {:>3}  {:>3}  {:>4}  {:>3}  Form",
+        "#",
+        "Team",
+        "P",
+        "W",
+        "D",
+        "L",
+        "GF",
+        "GA",
+        "GD",
+        "Pts",
+        width = width
+    )];
+
+    lines.extend(group.standings.iter().map(|row| standings_row(row, width)));
+
+    lines
+}
+
+/// Format a row of the standings, padding the team name to `width`
+fn standings_row(row: &ApiStandingRow, width: usize) -> String {
+    format!(
+        "{:>3}  {:<width$}  {:>2}  {:>2}  {:>2}  {:>2}  {:>3}  {:>3}  {:>+4}  {:>3}  {}",
+        row.rank,
+        row.competitor.name,
+        row.played,
+        row.win,
+        row.draw,
+        row.loss,
+        row.goals_for,
+        row.goals_against,
+        row.goals_diff,
+        row.points,
+        row.form.as_deref().unwrap_or("-"),
+        width = width
+    )
+    .trim_end()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standings() -> ApiSeasonStandings {
+        let row = |rank, id, name, win, draw, loss, goals_for, goals_against, form: &str| {
+            format!(
+                r#"{{"rank": {rank}, "competitor": {{"id": "{id}", "name": "{name}"}},
+                "played": {}, "win": {win}, "draw": {draw}, "loss": {loss},
+                "goals_for": {goals_for}, "goals_against": {goals_against},
+                "goals_diff": {}, "points": {}, "form": "{form}"}}"#,
+                win + draw + loss,
+                goals_for - goals_against,
+                win * 3 + draw,
+            )
+        };
+
+        serde_json::from_str(&format!(
+            r#"{{"standings": [
+                {{"type": "total", "groups": [{{"name": "Premier League", "standings": [{}, {}]}}]}},
+                {{"type": "home", "groups": [{{"standings": [{}]}}]}}
+            ]}}"#,
+            row(1, "sr:competitor:17", "Manchester City", 28, 7, 3, 96, 34, "WWWWW"),
+            row(2, "sr:competitor:42", "Arsenal FC", 28, 5, 5, 91, 29, "WWLWW"),
+            row(1, "sr:competitor:42", "Arsenal FC", 15, 2, 2, 48, 16, "WWWDW"),
+        ))
+        .unwrap()
+    }
+
+    // test the groups of each standing type are found
+    #[test]
+    fn test_standing_groups() {
+        let standings = standings();
+
+        let total = standing_groups(&standings, StandingType::Total);
+        assert_eq!(total.len(), 1);
+        assert_eq!(total[0].standings.len(), 2);
+        assert_eq!(total[0].standings[1].points, 89);
+
+        let home = standing_groups(&standings, StandingType::Home);
+        assert_eq!(home[0].standings[0].competitor.name, "Arsenal FC");
+
+        assert!(standing_groups(&standings, StandingType::Away).is_empty());
+    }
+
+    // test the standings are formatted as an aligned table
+    #[test]
+    fn test_standings_table() {
+        let standings = standings();
+        let groups = standing_groups(&standings, StandingType::Total);
+
+        assert_eq!(
+            standings_table(&groups[0]),
+            [
+                "  #  Team              P   W   D   L   GF   GA    GD  Pts  Form",
+                "  1  Manchester City  38  28   7   3   96   34   +62   91  WWWWW",
+                "  2  Arsenal FC       38  28   5   5   91   29   +62   89  WWLWW",
+            ]
+        );
+    }
+}
@@ -1,10 +1,22 @@
-use std::{collections::HashMap, fs, path::Path, time::Duration};
+use std::{
+    fs,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use anyhow::{Context, Result};
-use chrono::NaiveDate;
-use reqwest::Client;
-use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
-use tokio::time::sleep;
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use log::{debug, info, warn};
+use serde::{
+    de::{self, IgnoredAny},
+    Deserialize,
+    Deserializer,
+    Serialize,
+    Serializer,
+};
+
+use crate::error::{Error, Result};
 
 
 /// Custom function to serialize a chrono::NaiveDate into a date string
@@ -25,103 +37,445 @@ where
     NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(de::Error::custom)
 }
 
+/// API response stored in the cache, along with when and from which URL it
+/// was fetched
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CacheEntry<T> {
+    pub fetched_at: DateTime<Utc>,
+    pub url: String,
+    pub data: T,
+}
+
+impl<T> CacheEntry<T> {
+    /// Create a new [`CacheEntry`] fetched now
+    pub fn new(url: &str, data: T) -> Self {
+        Self {
+            fetched_at: Utc::now(),
+            url: url.to_string(),
+            data,
+        }
+    }
+
+    /// Time elapsed since the entry was fetched
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.fetched_at).to_std().unwrap_or_default()
+    }
+
+    /// Check if the entry is younger than the `ttl`
+    pub fn is_fresh(&self, ttl: Duration) -> bool {
+        self.age() < ttl
+    }
+}
+
+/// How the [`Cache`] is used when fetching data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CacheMode {
+    /// Use fresh cache entries, request missing or expired ones
+    #[default]
+    Default,
+    /// Ignore the cache and request everything, updating the cache
+    Refresh,
+    /// Only use the cache regardless of its age, never make requests
+    Offline,
+}
+
+/// Cache of API responses, stored as JSON files under a directory
+#[derive(Debug, Clone)]
+pub struct Cache {
+    location: String,
+    mode: CacheMode,
+}
+
+impl Cache {
+    /// Create a new [`Cache`] stored under `location`
+    pub fn new(location: &str, mode: CacheMode) -> Self {
+        Self {
+            location: location.to_string(),
+            mode,
+        }
+    }
+
+    /// Get the directory the cache is stored under
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Get how the cache is used
+    pub fn mode(&self) -> CacheMode {
+        self.mode
+    }
+
+    /// Get the path of the cache file for the parts identifying a request
+    pub fn path(&self, key: &[&str]) -> String {
+        cache_path(&self.location, key)
+    }
+}
+
+/// Whether a request is answered from the cache, see [`cached`]
+#[derive(Debug, PartialEq, Eq)]
+pub enum Lookup<T> {
+    /// The cached response is used
+    Hit(T),
+    /// The response must be requested from the API
+    Request,
+}
+
+/// Decide if the cache entry of a request answers it, according to the
+/// [`CacheMode`] and how long the entry stays fresh. Returns an error when
+/// offline without an entry, as the request can't be made
+pub fn cached<T>(
+    cache_path: &str,
+    entry: Option<CacheEntry<T>>,
+    mode: CacheMode,
+    ttl: Duration,
+) -> Result<Lookup<T>> {
+    match (mode, entry) {
+        (CacheMode::Refresh, _) => Ok(Lookup::Request),
+        (CacheMode::Offline, Some(entry)) => Ok(Lookup::Hit(entry.data)),
+        (CacheMode::Offline, None) => Err(Error::Cache {
+            path: cache_path.to_string(),
+            source: io::Error::new(
+                io::ErrorKind::NotFound,
+                "no cached response available while offline",
+            ),
+        }),
+        (CacheMode::Default, Some(entry)) if entry.is_fresh(ttl) => Ok(Lookup::Hit(entry.data)),
+        (CacheMode::Default, Some(entry)) => {
+            info!(
+                "Cache expired, fetched {} ago.",
+                humantime::format_duration(Duration::from_secs(entry.age().as_secs()))
+            );
+
+            Ok(Lookup::Request)
+        },
+        (CacheMode::Default, None) => Ok(Lookup::Request),
+    }
+}
+
+/// Build the path of a cache file from the parts identifying the request, so
+/// responses for different competitions, seasons and competitors never
+/// overwrite each other. Characters that are not safe in file names, like the
+/// `:` in `sr:season:105353`, are replaced with `_`
+pub fn cache_path(cache_location: &str, parts: &[&str]) -> String {
+    let mut path = PathBuf::from(cache_location);
+
+    for part in parts {
+        let part: String = part
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+                _ => '_',
+            })
+            .collect();
+
+        path.push(part);
+    }
+
+    path.to_string_lossy().into_owned()
+}
+
+/// Function to list every file stored in the cache directory and its
+/// subdirectories
+pub fn list_cache_files(cache_dir: &Path) -> Result<Vec<PathBuf>> {
+    let cache_error = |source| Error::Cache {
+        path: cache_dir.display().to_string(),
+        source,
+    };
+
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(cache_dir).map_err(cache_error)? {
+        let entry = entry.map_err(cache_error)?;
+        let path = entry.path();
+
+        // don't follow links to directories outside of the cache
+        if entry.file_type().map_err(cache_error)?.is_dir() {
+            files.extend(list_cache_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Read when and from which URL a cache file was fetched, skipping the data.
+/// Returns `None` when the file is not a cache entry
+pub fn read_cache_entry_info(path: &Path) -> Option<CacheEntry<IgnoredAny>> {
+    let file_content = fs::read_to_string(path).ok()?;
+
+    serde_json::from_str(&file_content).ok()
+}
+
+/// Remove every cache entry stored in the cache directory, and the
+/// directories left empty. Refuses to remove anything when the directory
+/// holds files that are not cache entries, so a cache location pointing to
+/// the wrong directory can't wipe it. Returns the removed files
+pub fn clear_cache(cache_dir: &Path) -> Result<Vec<PathBuf>> {
+    let files = list_cache_files(cache_dir)?;
+
+    if let Some(path) = files
+        .iter()
+        .find(|path| read_cache_entry_info(path).is_none())
+    {
+        return Err(Error::Cache {
+            path: cache_dir.display().to_string(),
+            source: io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} is not a cache entry, refusing to clear the directory",
+                    path.display()
+                ),
+            ),
+        });
+    }
+
+    for path in files.iter() {
+        fs::remove_file(path).map_err(|source| Error::Cache {
+            path: path.display().to_string(),
+            source,
+        })?;
+    }
+
+    remove_empty_dirs(cache_dir)?;
+
+    Ok(files)
+}
+
+/// Remove a directory and its subdirectories, as long as they are empty
+fn remove_empty_dirs(dir: &Path) -> Result<()> {
+    let cache_error = |source| Error::Cache {
+        path: dir.display().to_string(),
+        source,
+    };
+
+    for entry in fs::read_dir(dir).map_err(cache_error)? {
+        let entry = entry.map_err(cache_error)?;
+
+        if entry.file_type().map_err(cache_error)?.is_dir() {
+            remove_empty_dirs(&entry.path())?;
+        }
+    }
+
+    fs::remove_dir(dir).map_err(cache_error)
+}
+
 /// Function to check if cache exists and read from it
-pub fn read_from_cache<T>(cache_path: &str) -> Result<Option<T>>
+pub fn read_from_cache<T>(cache_path: &str) -> Result<Option<CacheEntry<T>>>
 where
     T: for<'de> Deserialize<'de>,
 {
     let cache_file = Path::new(cache_path);
 
-    print!("Checking for cache file at: {} | ", cache_path);
+    debug!("Checking for cache file at: {}", cache_path);
 
     if cache_file.exists() {
-        println!("Cache found. Reading from file...");
+        debug!("Cache found. Reading from file...");
+
+        let file_content = fs::read_to_string(cache_file).map_err(|source| Error::Cache {
+            path: cache_path.to_string(),
+            source,
+        })?;
 
-        let file_content =
-            fs::read_to_string(cache_file).context("Failed to read the cache file")?;
+        // cache files written before entries were timestamped can not be
+        // trusted, treat them as missing
+        return match serde_json::from_str(&file_content) {
+            Ok(entry) => Ok(Some(entry)),
+            Err(_) => {
+                warn!(
+                    "Cache file {} is not a valid cache entry, ignoring it",
+                    cache_path
+                );
 
-        let data: T =
-            serde_json::from_str(&file_content).context("Failed to deserialize JSON from cache")?;
-        return Ok(Some(data));
+                Ok(None)
+            },
+        };
     }
 
+    debug!("Cache not found.");
+
     Ok(None)
 }
 
 /// Function to write API response to cache
-pub fn write_to_cache<T>(cache_path: &str, data: &T) -> Result<()>
+pub fn write_to_cache<T>(cache_path: &str, entry: &CacheEntry<T>) -> Result<()>
 where
     T: Serialize,
 {
+    let cache_error = |source| Error::Cache {
+        path: cache_path.to_string(),
+        source,
+    };
+
+    // failing to serialize the entry means it can't be cached, not that a
+    // response couldn't be decoded
     let json_string =
-        serde_json::to_string_pretty(data).context("Failed to serialize data to JSON")?;
+        serde_json::to_string_pretty(entry).map_err(|err| cache_error(io::Error::from(err)))?;
+
+    if let Some(cache_dir) = Path::new(cache_path).parent() {
+        fs::create_dir_all(cache_dir).map_err(cache_error)?;
+    }
 
-    fs::write(cache_path, json_string).context("Failed to write the cache file")?;
+    fs::write(cache_path, json_string).map_err(cache_error)?;
 
     Ok(())
 }
 
-/// Function to request data from the API
-pub async fn request_data<T>(
-    client: &Client,
-    url: &str,
-    query_params: &HashMap<&str, &str>,
-    headers: &HashMap<&str, &str>,
-    access_level: &str,
-) -> Result<T>
-where
-    T: for<'de> Deserialize<'de>,
-{
-    println!("Cache not found. Making HTTP request...");
-    let mut request = client.get(url).query(query_params);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for (key, value) in headers {
-        request = request.header(*key, *value);
+    fn temp_cache_path(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("talent-scout-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        dir.join(name).to_string_lossy().into_owned()
     }
 
-    if access_level == "trial" {
-        // Setting a sleep time to avoid the 1 query per second rate limiting
-        sleep(Duration::from_secs(2)).await;
+    // test cache entries keep their data, url and fetch time
+    #[test]
+    fn test_cache_roundtrip() {
+        let cache_path = temp_cache_path("roundtrip.json");
+
+        let entry = CacheEntry::new("https://example.com/seasons.json", vec![1, 2, 3]);
+        write_to_cache(&cache_path, &entry).unwrap();
+
+        let cached = read_from_cache::<Vec<u32>>(&cache_path).unwrap().unwrap();
+
+        assert_eq!(cached.data, vec![1, 2, 3]);
+        assert_eq!(cached.url, "https://example.com/seasons.json");
+        assert_eq!(cached.fetched_at, entry.fetched_at);
+        assert!(cached.is_fresh(Duration::from_secs(60)));
+        assert!(!cached.is_fresh(Duration::ZERO));
     }
 
-    let response = request.send().await.context("Failed to send request")?;
+    // test fresh entries are used and expired or missing ones requested
+    #[test]
+    fn test_cached_default() {
+        let ttl = Duration::from_secs(60);
+        let fresh = || Some(CacheEntry::new("https://example.com", 1));
+        let mut expired = CacheEntry::new("https://example.com", 2);
+        expired.fetched_at -= chrono::Duration::seconds(120);
 
-    if !response.status().is_success() {
-        anyhow::bail!("Request failed with status: {}", response.status());
+        assert_eq!(
+            cached("entry.json", fresh(), CacheMode::Default, ttl).unwrap(),
+            Lookup::Hit(1)
+        );
+        assert_eq!(
+            cached("entry.json", Some(expired), CacheMode::Default, ttl).unwrap(),
+            Lookup::Request
+        );
+        assert_eq!(
+            cached::<u32>("entry.json", None, CacheMode::Default, ttl).unwrap(),
+            Lookup::Request
+        );
     }
 
-    let data = response
-        .json::<T>()
-        .await
-        .context("Failed to parse JSON response")?;
+    // test offline uses entries regardless of their age, and fails without
+    // one
+    #[test]
+    fn test_cached_offline() {
+        let entry = CacheEntry::new("https://example.com", 1);
 
-    Ok(data)
-}
+        assert_eq!(
+            cached(
+                "entry.json",
+                Some(entry),
+                CacheMode::Offline,
+                Duration::ZERO
+            )
+            .unwrap(),
+            Lookup::Hit(1)
+        );
+        assert!(matches!(
+            cached::<u32>("entry.json", None, CacheMode::Offline, Duration::ZERO),
+            Err(Error::Cache { path, .. }) if path == "entry.json"
+        ));
+    }
 
-// TODO update query params to use proper types
-// TODO update for urls to be enums
-/// Function to fetch data, either from cache or by requesting
-pub async fn fetch_data<T>(
-    client: &Client,
-    url: &str,
-    query_params: &HashMap<&str, &str>,
-    headers: &HashMap<&str, &str>,
-    cache_path: &str,
-    access_level: &str,
-) -> Result<T>
-where
-    T: for<'de> Deserialize<'de> + Serialize,
-{
-    // Read from cache if it exists
-    if let Some(data) = read_from_cache(cache_path)? {
-        return Ok(data);
+    // test refresh requests even fresh entries
+    #[test]
+    fn test_cached_refresh() {
+        let entry = CacheEntry::new("https://example.com", 1);
+
+        assert_eq!(
+            cached(
+                "entry.json",
+                Some(entry),
+                CacheMode::Refresh,
+                Duration::from_secs(60)
+            )
+            .unwrap(),
+            Lookup::Request
+        );
+    }
+
+    // test cache paths are scoped by every part of the request
+    #[test]
+    fn test_cache_path() {
+        let path = cache_path(
+            "cache",
+            &["trial", "seasons", "sr:season:1", "competitors.json"],
+        );
+
+        assert_eq!(
+            Path::new(&path),
+            Path::new("cache/trial/seasons/sr_season_1/competitors.json")
+        );
     }
 
-    // Request data from the API
-    let data = request_data(client, url, query_params, headers, access_level).await?;
+    // test cache entries are written to nested directories
+    #[test]
+    fn test_cache_nested_directories() {
+        let cache_dir = temp_cache_path("nested");
+        let path = cache_path(
+            &cache_dir,
+            &["trial", "seasons", "sr:season:1", "competitors.json"],
+        );
+
+        write_to_cache(&path, &CacheEntry::new("https://example.com", 1)).unwrap();
 
-    // Write data to cache
-    write_to_cache(cache_path, &data)?;
+        let files = list_cache_files(Path::new(&cache_dir)).unwrap();
 
-    Ok(data)
+        assert_eq!(files, vec![PathBuf::from(&path)]);
+    }
+
+    // test clearing the cache removes its entries and directories, and
+    // refuses to touch directories holding anything else
+    #[test]
+    fn test_clear_cache() {
+        let cache_dir = temp_cache_path("clear");
+        let path = cache_path(&cache_dir, &["trial", "seasons.json"]);
+
+        write_to_cache(&path, &CacheEntry::new("https://example.com", 1)).unwrap();
+
+        let other = cache_path(&cache_dir, &["notes.txt"]);
+        fs::write(&other, "not a cache entry").unwrap();
+
+        assert!(matches!(
+            clear_cache(Path::new(&cache_dir)),
+            Err(Error::Cache { .. })
+        ));
+        assert!(Path::new(&path).exists());
+
+        fs::remove_file(&other).unwrap();
+
+        assert_eq!(
+            clear_cache(Path::new(&cache_dir)).unwrap(),
+            vec![PathBuf::from(&path)]
+        );
+        assert!(!Path::new(&cache_dir).exists());
+    }
+
+    // test cache files without metadata are treated as missing
+    #[test]
+    fn test_cache_without_metadata() {
+        let cache_path = temp_cache_path("legacy.json");
+
+        fs::write(&cache_path, "[1, 2, 3]").unwrap();
+
+        assert!(read_from_cache::<Vec<u32>>(&cache_path).unwrap().is_none());
+    }
 }